@@ -0,0 +1,277 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::error::{Error, Result};
+use crate::side_repo::SideRepo;
+use crate::timestamp;
+
+const RUN_START: &str = ">>> ";
+const RUN_END: &str = "<<< ";
+const RUN_OUTPUT: &str = "| ";
+
+/// Rotate the log once it grows past this size.
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Passes the run id from [`spawn`] to the detached process.
+const RUN_ID_VAR: &str = "GIT_SIDE_RUN_ID";
+
+/// How often a run waiting for another one checks the lock again.
+const LOCK_POLL: Duration = Duration::from_millis(100);
+
+/// A lock older than this was left by a run that died; it is broken.
+const STALE_LOCK_AGE: Duration = Duration::from_secs(10 * 60);
+
+/// A single recorded run from the log.
+pub struct RunEntry {
+    /// Tags the run's lines in the log.
+    pub id: String,
+    /// When the run started (RFC 3339, UTC).
+    pub started: String,
+    /// What was run (e.g. `auto`, `hook post-commit`).
+    pub label: String,
+    /// Output captured while the run was in progress.
+    pub output: Vec<String>,
+    /// `None` while running (or if the process died), `Some(Ok)` or `Some(Err(message))` once done.
+    pub outcome: Option<std::result::Result<(), String>>,
+}
+
+impl RunEntry {
+    /// Whether the run finished with an error or never finished.
+    #[must_use]
+    pub const fn failed(&self) -> bool {
+        !matches!(self.outcome, Some(Ok(())))
+    }
+}
+
+/// Path to the run log inside the side repo's git dir.
+#[must_use]
+pub fn log_path(repo: &SideRepo) -> PathBuf {
+    repo.git_dir.join("git-side.log")
+}
+
+/// Open the run log for appending, rotating it if it has grown too large.
+fn open_log(path: &Path) -> Result<File> {
+    if fs::metadata(path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let _ = fs::rename(path, path.with_extension("log.1"));
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|e| Error::WriteFile {
+            path: path.to_path_buf(),
+            source: e,
+        })
+}
+
+/// Exclusive hold on the side repo for one recorded run, so runs never interleave.
+/// Released on drop.
+struct RunLock {
+    path: PathBuf,
+}
+
+impl RunLock {
+    /// Wait until no other run holds the lock, then take it.
+    fn acquire(repo: &SideRepo) -> Result<Self> {
+        let path = repo.git_dir.join("git-side.lock");
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = writeln!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let stale = fs::metadata(&path)
+                        .and_then(|m| m.modified())
+                        .ok()
+                        .and_then(|modified| modified.elapsed().ok())
+                        .is_some_and(|age| age > STALE_LOCK_AGE);
+                    if stale {
+                        let _ = fs::remove_file(&path);
+                    } else {
+                        thread::sleep(LOCK_POLL);
+                    }
+                }
+                Err(e) => {
+                    return Err(Error::WriteFile { path, source: e });
+                }
+            }
+        }
+    }
+}
+
+impl Drop for RunLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// A fresh run id: the process id and the sub-second clock, in hex.
+fn new_run_id() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_nanos());
+    format!("{:x}-{nanos:x}", std::process::id())
+}
+
+/// Where a detached run's output is captured until [`record`] moves it into the log.
+fn output_path(repo: &SideRepo, id: &str) -> PathBuf {
+    repo.git_dir.join(format!("git-side.run-{id}"))
+}
+
+/// Re-run git-side with `args` in a detached process whose output ends up in the run log.
+/// `args` should include the command's `--detached` flag so the child records its own outcome.
+///
+/// # Errors
+///
+/// Returns an error if the output file cannot be created or the process cannot be spawned.
+pub fn spawn(repo: &SideRepo, args: &[&str]) -> Result<()> {
+    repo.ensure_initialized()?;
+
+    let id = new_run_id();
+    let path = output_path(repo, &id);
+    let stdout = File::create(&path).map_err(|e| Error::WriteFile {
+        path: path.clone(),
+        source: e,
+    })?;
+    let stderr = stdout.try_clone().map_err(|e| Error::WriteFile {
+        path: path.clone(),
+        source: e,
+    })?;
+
+    let exe = std::env::current_exe()
+        .map_err(|e| Error::GitCommandFailed(format!("cannot locate git-side binary: {e}")))?;
+
+    let mut command = Command::new(exe);
+    command
+        .args(args)
+        .env(RUN_ID_VAR, &id)
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr);
+
+    // Detach from the terminal so the child outlives the hook and ignores Ctrl-C
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const DETACHED_PROCESS: u32 = 0x0000_0008;
+        command.creation_flags(DETACHED_PROCESS);
+    }
+
+    command
+        .spawn()
+        .map_err(|e| Error::GitCommandFailed(format!("failed to start background run: {e}")))?;
+    Ok(())
+}
+
+/// Run `f` and record its start, output and outcome in the run log, one run at a time.
+///
+/// Waits while another run holds the side repo. Output printed by `f` only lands in the
+/// log for runs started through [`spawn`]; each line is tagged with the run id.
+///
+/// # Errors
+///
+/// Returns the error from `f`, or an error if the log cannot be written.
+pub fn record(repo: &SideRepo, label: &str, f: impl FnOnce() -> Result<()>) -> Result<()> {
+    repo.ensure_initialized()?;
+    let id = std::env::var(RUN_ID_VAR)
+        .ok()
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(new_run_id);
+    let _lock = RunLock::acquire(repo)?;
+    let path = log_path(repo);
+
+    let write = |line: &str| -> Result<()> {
+        let mut file = open_log(&path)?;
+        writeln!(file, "{line}").map_err(|e| Error::WriteFile {
+            path: path.clone(),
+            source: e,
+        })
+    };
+
+    write(&format!("{RUN_START}{id} {} {label}", timestamp::now()))?;
+    let result = f();
+
+    let _ = io::stdout().flush();
+    let output = output_path(repo, &id);
+    if let Ok(captured) = fs::read_to_string(&output) {
+        for line in captured.lines() {
+            write(&format!("{RUN_OUTPUT}{id} {line}"))?;
+        }
+        let _ = fs::remove_file(&output);
+    }
+
+    match &result {
+        Ok(()) => write(&format!("{RUN_END}{id} ok"))?,
+        // Keep the outcome on one line so the log stays parseable
+        Err(e) => write(&format!(
+            "{RUN_END}{id} failed: {}",
+            e.to_string().lines().collect::<Vec<_>>().join("; ")
+        ))?,
+    }
+    result
+}
+
+/// Read all recorded runs, oldest first.
+///
+/// # Errors
+///
+/// Returns an error if the log exists but cannot be read.
+pub fn entries(repo: &SideRepo) -> Result<Vec<RunEntry>> {
+    let path = log_path(repo);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path).map_err(|e| Error::ReadFile {
+        path: path.clone(),
+        source: e,
+    })?;
+
+    let mut entries: Vec<RunEntry> = Vec::new();
+    for line in content.lines() {
+        if let Some(header) = line.strip_prefix(RUN_START) {
+            let mut fields = header.splitn(3, ' ');
+            let (id, started, label) = (fields.next(), fields.next(), fields.next());
+            entries.push(RunEntry {
+                id: id.unwrap_or_default().to_string(),
+                started: started.unwrap_or_default().to_string(),
+                label: label.unwrap_or_default().to_string(),
+                output: Vec::new(),
+                outcome: None,
+            });
+            continue;
+        }
+
+        // Output and outcome lines belong to the latest run with their id
+        let (tagged, is_output) = match (line.strip_prefix(RUN_OUTPUT), line.strip_prefix(RUN_END)) {
+            (Some(tagged), _) => (tagged, true),
+            (None, Some(tagged)) => (tagged, false),
+            (None, None) => continue,
+        };
+        let (id, rest) = tagged.split_once(' ').unwrap_or((tagged, ""));
+        let Some(entry) = entries.iter_mut().rev().find(|e| e.id == id) else {
+            continue;
+        };
+        if is_output {
+            entry.output.push(rest.to_string());
+        } else {
+            entry.outcome = Some(
+                rest.strip_prefix("failed: ")
+                    .map_or(Ok(()), |message| Err(message.to_string())),
+            );
+        }
+    }
+
+    Ok(entries)
+}
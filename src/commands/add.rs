@@ -1,11 +1,12 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use colored::Colorize;
 
 use crate::error::{Error, Result};
 use crate::git;
+use crate::settings::Settings;
 use crate::side_repo::SideRepo;
-use crate::tracked::TrackedPaths;
+use crate::tracked::{self, TrackedPaths};
 
 /// Add a path to side tracking.
 ///
@@ -16,12 +17,7 @@ pub fn run(path: &Path) -> Result<()> {
     let work_tree = git::repo_root()?;
 
     // Normalize path: make it relative to work tree
-    let relative_path = if path.is_absolute() {
-        path.strip_prefix(&work_tree)
-            .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
-    } else {
-        path.to_path_buf()
-    };
+    let relative_path = tracked::normalize(&work_tree, path);
 
     // Check if path exists
     let full_path = work_tree.join(&relative_path);
@@ -41,12 +37,36 @@ pub fn run(path: &Path) -> Result<()> {
         return Err(Error::PathAlreadyTracked(relative_path));
     }
 
+    // A single file over the size limit is refused outright
+    let max_file_size = Settings::load(&repo)?.max_file_size;
+    if let Some(limit) = max_file_size
+        && full_path.is_file()
+    {
+        let size = full_path.metadata().map_or(0, |m| m.len());
+        if size > limit {
+            return Err(Error::FileTooLarge {
+                path: relative_path,
+                size,
+                limit,
+            });
+        }
+    }
+
     // Add to tracked list
     tracked.add(&relative_path);
     tracked.save()?;
 
-    // Stage the path
-    repo.stage(&relative_path)?;
+    // Stage the path; inside a directory, large files are skipped
+    let skipped = repo.stage_paths(std::slice::from_ref(&relative_path), max_file_size)?;
+    if let Some(limit) = max_file_size {
+        for (path, size) in skipped {
+            eprintln!(
+                "{} {} ({size} bytes, side.maxFileSize is {limit})",
+                "Skipped large file:".yellow(),
+                path.display()
+            );
+        }
+    }
 
     // Stage .side-tracked file itself
     repo.stage_tracked_file()?;
@@ -59,3 +79,33 @@ pub fn run(path: &Path) -> Result<()> {
 
     Ok(())
 }
+
+/// Interactively stage hunks of side-tracked files (`git add -p` on the side index).
+/// Without a path, every tracked path is offered.
+///
+/// # Errors
+///
+/// Returns an error if nothing is tracked, the path is not tracked, or staging fails.
+pub fn patch(path: Option<&Path>) -> Result<()> {
+    let repo = SideRepo::open()?;
+    if !repo.is_initialized() {
+        return Err(Error::NoTrackedPaths);
+    }
+
+    let tracked = TrackedPaths::load(&repo)?;
+    if tracked.is_empty() {
+        return Err(Error::NoTrackedPaths);
+    }
+
+    let scope: Vec<PathBuf> = if let Some(path) = path {
+        let relative_path = tracked::normalize(&repo.work_tree, path);
+        if !tracked.covers(&relative_path) {
+            return Err(Error::PathNotTracked(relative_path));
+        }
+        vec![relative_path]
+    } else {
+        tracked.paths().iter().cloned().collect()
+    };
+
+    repo.stage_patch(&scope)
+}
@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+use colored::Colorize;
+
+use crate::commands::log::resolve_snapshot;
+use crate::error::{Error, Result};
+use crate::side_repo::SideRepo;
+
+/// Archive formats `git archive` can write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ArchiveFormat {
+    /// Plain tar
+    Tar,
+    /// Gzip-compressed tar
+    #[value(name = "tar.gz")]
+    TarGz,
+    /// Zip
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Guess the format from the output file name, as `git archive -o` does (tar otherwise).
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        let name = path.to_string_lossy();
+        if name.ends_with(".zip") {
+            Self::Zip
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Self::TarGz
+        } else {
+            Self::Tar
+        }
+    }
+
+    /// Name of the format for `git archive --format`.
+    #[must_use]
+    pub const fn git_name(self) -> &'static str {
+        match self {
+            Self::Tar => "tar",
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// What to archive and where.
+#[derive(Debug)]
+pub struct ArchiveOptions<'a> {
+    /// Side revision to archive.
+    pub rev: &'a str,
+    /// Archive the side snapshot closest to this main repo revision instead.
+    pub at: Option<&'a str>,
+    /// Format; guessed from `output` when not given.
+    pub format: Option<ArchiveFormat>,
+    /// Also include the `.side-tracked` manifest.
+    pub manifest: bool,
+    /// File to write.
+    pub output: &'a Path,
+}
+
+/// Write the side-tracked files of a side revision to a tar or zip file,
+/// without touching the work tree.
+///
+/// # Errors
+///
+/// Returns an error if the side repo is not initialized, the revision does not
+/// exist, or the archive cannot be written.
+pub fn run(options: &ArchiveOptions<'_>) -> Result<()> {
+    let repo = SideRepo::open()?;
+    if !repo.is_initialized() {
+        return Err(Error::NoTrackedPaths);
+    }
+
+    let rev = match options.at {
+        Some(main_rev) => resolve_snapshot(&repo, main_rev)?,
+        None => options.rev.to_string(),
+    };
+    let format = options
+        .format
+        .unwrap_or_else(|| ArchiveFormat::from_path(options.output));
+
+    // git runs from the project root, so the output path must not stay relative
+    let output = std::path::absolute(options.output).map_err(|e| Error::WriteFile {
+        path: options.output.to_path_buf(),
+        source: e,
+    })?;
+    let output_str = output.to_string_lossy();
+    let format_arg = format!("--format={}", format.git_name());
+
+    let mut args = vec![
+        "archive",
+        format_arg.as_str(),
+        "-o",
+        output_str.as_ref(),
+        rev.as_str(),
+    ];
+    if !options.manifest {
+        args.extend(["--", ".", ":(exclude).side-tracked"]);
+    }
+    repo.git(&args)?;
+
+    // A snapshot found through --at is a full SHA; abbreviate it as resolve_snapshot did
+    let label = match options.at {
+        Some(_) => &rev[..7.min(rev.len())],
+        None => rev.as_str(),
+    };
+
+    println!(
+        "{} Archived side files at {} into {} ({})",
+        "Done.".green().bold(),
+        label.cyan(),
+        options.output.display(),
+        format.git_name()
+    );
+    Ok(())
+}
@@ -1,24 +1,94 @@
 use colored::Colorize;
 
+use crate::background;
 use crate::error::{Error, Result};
-use crate::git;
+use crate::message;
+use crate::settings::Settings;
 use crate::side_repo::SideRepo;
 use crate::tracked::TrackedPaths;
 
-/// Auto-commit: sync all tracked paths using the last main repo commit message.
+/// Where `auto` runs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum RunMode {
+    /// In this process, attached to the terminal.
+    #[default]
+    Foreground,
+    /// Spawn a detached process and return immediately.
+    Background,
+    /// Internal: this is the detached process, record the run in the log.
+    Detached,
+}
+
+/// How `auto` should run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AutoOptions {
+    /// Where to run.
+    pub mode: RunMode,
+    /// Only print errors.
+    pub quiet: bool,
+    /// Do not push after committing, whatever `side.autoPush` says.
+    pub no_push: bool,
+}
+
+/// Auto-commit: sync all tracked paths using the project's message template.
 ///
 /// # Errors
 ///
 /// Returns an error if no paths are tracked, staging fails, or commit fails.
-pub fn run() -> Result<()> {
+pub fn run(options: AutoOptions) -> Result<()> {
     let repo = SideRepo::open()?;
 
     if !repo.is_initialized() {
         return Err(Error::NoTrackedPaths);
     }
 
+    if options.mode == RunMode::Detached {
+        colored::control::set_override(false);
+        return background::record(&repo, "auto", || sync(&repo, false, options.no_push));
+    }
+
+    if options.mode == RunMode::Background {
+        let mut args = vec!["auto", "--detached"];
+        if options.no_push {
+            args.push("--no-push");
+        }
+        background::spawn(&repo, &args)?;
+        if !options.quiet {
+            println!(
+                "{} {}",
+                "[git-side]".dimmed(),
+                "Auto-sync running in background (see 'git side hook log').".green()
+            );
+        }
+        return Ok(());
+    }
+
+    sync(&repo, options.quiet, options.no_push)
+}
+
+/// Stage all tracked paths and commit them using the project's message template,
+/// then push unless `no_push` is set or `side.autoPush` is off.
+///
+/// The push never forces: if the remote has diverged, a warning is printed and
+/// the commit stays local.
+///
+/// # Errors
+///
+/// Returns an error if no paths are tracked, staging fails, or commit fails.
+pub fn sync(repo: &SideRepo, quiet: bool, no_push: bool) -> Result<()> {
+    // Per-branch mode: commit onto the side branch matching the main branch
+    if repo.per_branch()? {
+        let branch = repo.branch_for_main()?;
+        if repo.current_branch()?.as_deref() != Some(branch.as_str()) {
+            repo.attach_branch(&branch)?;
+        }
+    }
+
+    let settings = Settings::load(repo)?;
+    let push = !no_push && settings.auto_push;
+
     // Load tracked paths
-    let tracked = TrackedPaths::load(&repo)?;
+    let tracked = TrackedPaths::load(repo)?;
 
     if tracked.is_empty() {
         return Err(Error::NoTrackedPaths);
@@ -30,29 +100,48 @@ pub fn run() -> Result<()> {
     // Get the raw tracked paths for staging (we stage the tracked paths, not expanded files)
     let tracked_paths: Vec<_> = tracked.paths().iter().cloned().collect();
 
+    // Missing roots are still staged (their deletion is recorded) but worth flagging
+    for missing in tracked.missing(&repo.work_tree) {
+        eprintln!(
+            "{} {} {} {}",
+            "[git-side]".dimmed(),
+            "warning: tracked path not found:".yellow(),
+            missing.display(),
+            "(run 'git side prune' to stop tracking it)".dimmed()
+        );
+    }
+
     // Two-pass staging:
     // Pass 1: update tracked files (modifications + deletions) — errors ignored
-    repo.stage_update(&tracked_paths);
-
-    // Pass 2: add new files
-    repo.stage_new(&tracked_paths)?;
+    // Pass 2: add new files under the tracked paths that exist
+    // Files over side.maxFileSize are left out before they are staged
+    let skipped = repo.stage_paths(&tracked_paths, settings.max_file_size)?;
+    if let Some(limit) = settings.max_file_size {
+        for (path, size) in skipped {
+            eprintln!(
+                "{} {} {} ({size} bytes, side.maxFileSize is {limit})",
+                "[git-side]".dimmed(),
+                "warning: skipped large file".yellow(),
+                path.display()
+            );
+        }
+    }
 
     // Stage .side-tracked file itself (self-aware versioning)
     repo.stage_tracked_file()?;
 
-    // Get last commit message from main repo
-    let message = git::last_commit_message()?;
-
-    if message.trim().is_empty() {
-        return Err(Error::GitCommandFailed(
-            "no commit message found in main repo".to_string(),
-        ));
-    }
+    // Build the message from the project template (default: last main repo message)
+    let message = message::for_auto(repo)?;
 
     let prefix = "[git-side]".dimmed();
 
     // Commit (will return NothingToCommit if nothing changed)
     match repo.commit(&message) {
+        Ok(()) if quiet => {
+            if push {
+                push_after_commit(repo, &settings, true);
+            }
+        }
         Ok(()) => {
             println!(
                 "{} {} {}",
@@ -65,11 +154,11 @@ pub fn run() -> Result<()> {
                 prefix,
                 files.len().to_string().cyan()
             );
-            // Try to push — silently ignored if no remote
-            if repo.push().is_ok() {
-                println!("{} {}", prefix, "Pushed to remote.".green().bold());
+            if push {
+                push_after_commit(repo, &settings, false);
             }
         }
+        Err(Error::NothingToCommit) if quiet => {}
         Err(Error::NothingToCommit) => {
             println!("{} {}", prefix, "Nothing to commit (side repo is up to date).".yellow());
         }
@@ -78,3 +167,57 @@ pub fn run() -> Result<()> {
 
     Ok(())
 }
+
+/// Push after an auto-commit, to every push target. A missing remote is only mentioned;
+/// a failed push is a warning with git's error (also in quiet mode), and the commit
+/// stays local for that remote.
+fn push_after_commit(repo: &SideRepo, settings: &Settings, quiet: bool) {
+    let prefix = "[git-side]".dimmed();
+
+    let results = repo.push(settings, false);
+    if results.is_empty() && !quiet {
+        println!("{} {}", prefix, "No remote configured, not pushed.".dimmed());
+    }
+
+    for (remote, result) in results {
+        match result {
+            Ok(_) if quiet => {}
+            Ok(kept) => {
+                println!("{} {} {}", prefix, "Pushed to".green().bold(), remote.cyan());
+                for refname in kept {
+                    println!(
+                        "{} {} {refname} (the remote has newer commits)",
+                        prefix,
+                        "left as is:".yellow()
+                    );
+                }
+            }
+            Err(e) => warn_push_failed(&remote, e),
+        }
+    }
+}
+
+/// Print a push failure for one remote, with git's error output.
+fn warn_push_failed(remote: &str, error: Error) {
+    let prefix = "[git-side]".dimmed();
+    match error {
+        e @ Error::PushRejected { .. } => {
+            eprintln!("{} {} {e}", prefix, "warning:".yellow());
+        }
+        e => {
+            eprintln!(
+                "{} {}",
+                prefix,
+                format!("warning: push to '{remote}' failed, side commits are not there yet:")
+                    .yellow()
+            );
+            let detail = match e {
+                Error::GitCommandFailed(stderr) => stderr,
+                e => e.to_string(),
+            };
+            for line in detail.lines().filter(|l| !l.trim().is_empty()) {
+                eprintln!("{prefix}     {line}");
+            }
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+
+use crate::commands::clone;
+use crate::error::{Error, Result};
+use crate::git;
+use crate::side_repo::SideRepo;
+
+/// Ref recorded in every bundle, named after the project's root commit, so `apply`
+/// can tell which project a bundle belongs to.
+const ROOT_MARKER_PREFIX: &str = "refs/git-side/root/";
+
+/// Where the tip of each branch at the last export is remembered.
+/// The next bundle only contains commits past these.
+const EXPORT_REF_PREFIX: &str = "refs/side-bundles/";
+
+/// Write this project's side branches to a bundle file.
+///
+/// Only commits added since the last bundle are included, unless `full` is set.
+///
+/// # Errors
+///
+/// Returns an error if the side repo is not initialized or the bundle cannot be written.
+pub fn create(file: &Path, full: bool) -> Result<()> {
+    let repo = SideRepo::open()?;
+    if !repo.is_initialized() {
+        return Err(Error::NoTrackedPaths);
+    }
+
+    let path = absolute(file)?;
+    let file_str = path.to_string_lossy();
+
+    let exported: Vec<String> = if full {
+        Vec::new()
+    } else {
+        list_refs(&repo, &export_prefix(&repo))?
+            .into_iter()
+            .map(|(refname, _)| refname)
+            .collect()
+    };
+
+    // Branches with commits the previous bundles did not carry
+    let prefix = repo.ref_prefix();
+    let mut changed = Vec::new();
+    for (refname, sha) in list_refs(&repo, &prefix)? {
+        if count_new(&repo, &[sha.as_str()], &exported)? > 0 {
+            changed.push((refname, sha));
+        }
+    }
+
+    let Some((_, marker_tip)) = changed.first() else {
+        println!(
+            "{}",
+            "Nothing new since the last bundle (use --full to export everything).".yellow()
+        );
+        return Ok(());
+    };
+
+    let tips: Vec<&str> = changed.iter().map(|(_, sha)| sha.as_str()).collect();
+    let commits = count_new(&repo, &tips, &exported)?;
+
+    // The marker must point at a bundled commit, or git leaves it out of the bundle
+    let marker = format!("{ROOT_MARKER_PREFIX}{}", repo.root_sha);
+    repo.git(&["update-ref", &marker, marker_tip])?;
+
+    let mut args = vec!["bundle", "create", "--quiet", file_str.as_ref()];
+    args.extend(changed.iter().map(|(refname, _)| refname.as_str()));
+    args.push(&marker);
+    if !exported.is_empty() {
+        args.push("--not");
+        args.extend(exported.iter().map(String::as_str));
+    }
+    let result = repo.git(&args);
+    let _ = repo.git(&["update-ref", "-d", &marker]);
+    result?;
+
+    for (refname, sha) in &changed {
+        let branch = refname.strip_prefix(&prefix).unwrap_or(refname);
+        repo.git(&["update-ref", &export_ref(&repo, branch), sha])?;
+    }
+
+    let scope = if exported.is_empty() {
+        "full"
+    } else {
+        "incremental"
+    };
+    println!(
+        "{} Bundled {} commit(s) on {} branch(es) into {} ({scope})",
+        "Done.".green().bold(),
+        commits.to_string().cyan(),
+        changed.len().to_string().cyan(),
+        file.display()
+    );
+    Ok(())
+}
+
+/// Bring the side branches in a bundle file into this project's side repo.
+///
+/// Branches are fast-forwarded; a branch with local commits the bundle lacks is
+/// left alone unless `force` is set. The checked-out branch also updates the work tree.
+///
+/// # Errors
+///
+/// Returns an error if the bundle is unreadable, belongs to another project, lacks
+/// commits it builds on, or would drop local side commits without `force`.
+pub fn apply(file: &Path, force: bool) -> Result<()> {
+    let repo = SideRepo::open()?;
+
+    let path = absolute(file)?;
+    let file_str = path.to_string_lossy();
+
+    let heads = bundle_heads(file, &repo.root_sha)?;
+
+    let fresh = !repo.is_initialized();
+    repo.ensure_initialized()?;
+
+    let result = repo
+        .git(&["bundle", "verify", &file_str])
+        .and_then(|_| apply_heads(&repo, &file_str, &heads, force));
+    if let Err(e) = result {
+        // Leave nothing half-applied behind so the apply can simply be retried
+        if fresh {
+            let _ = fs::remove_dir_all(&repo.git_dir);
+        }
+        return Err(e);
+    }
+
+    println!(
+        "{} Applied bundle {}",
+        "Done.".green().bold(),
+        file.display()
+    );
+    Ok(())
+}
+
+/// A side branch stored in a bundle.
+struct BundleHead {
+    /// Branch name, without the ref prefix.
+    branch: String,
+    /// Ref name inside the bundle.
+    refname: String,
+    sha: String,
+}
+
+/// Read the branches in a bundle, checking it was made for the project rooted at `root_sha`.
+fn bundle_heads(file: &Path, root_sha: &str) -> Result<Vec<BundleHead>> {
+    let listing = git::run(&["bundle", "list-heads", &file.to_string_lossy()])?;
+
+    let mut found = None;
+    let mut refs = Vec::new();
+    for line in listing.lines() {
+        let Some((sha, refname)) = line.split_once(' ') else {
+            continue;
+        };
+        match refname.strip_prefix(ROOT_MARKER_PREFIX) {
+            Some(root) => found = Some(root.to_string()),
+            None => refs.push((refname.to_string(), sha.to_string())),
+        }
+    }
+
+    let unrelated = |reason: String| Error::UnrelatedBundle {
+        path: file.to_path_buf(),
+        reason,
+    };
+    match found {
+        None => {
+            return Err(unrelated(
+                "it was not made with 'git side bundle create'".to_string(),
+            ));
+        }
+        Some(found) if found != root_sha => {
+            return Err(unrelated(format!("it was made for project {found}")));
+        }
+        Some(_) => {}
+    }
+
+    // Branches are stored per project in central storage, under refs/heads otherwise
+    let central_prefix = format!("refs/projects/{root_sha}/");
+    Ok(refs
+        .into_iter()
+        .filter_map(|(refname, sha)| {
+            let branch = refname
+                .strip_prefix("refs/heads/")
+                .or_else(|| refname.strip_prefix(&central_prefix))?
+                .to_string();
+            Some(BundleHead {
+                branch,
+                refname,
+                sha,
+            })
+        })
+        .collect())
+}
+
+/// Fetch the bundle's commits and move each side branch to its bundled tip.
+fn apply_heads(repo: &SideRepo, file: &str, heads: &[BundleHead], force: bool) -> Result<()> {
+    let mut fetch = vec!["fetch", "--quiet", "--no-write-fetch-head", file];
+    fetch.extend(heads.iter().map(|head| head.refname.as_str()));
+    repo.git(&fetch)?;
+
+    let current = repo.current_branch()?;
+    let mut diverged = Vec::new();
+    for BundleHead { branch, sha, .. } in heads {
+        let refname = repo.branch_ref(branch);
+        let is_current = current.as_deref() == Some(branch.as_str());
+        let local = repo
+            .git(&["rev-parse", "--verify", "--quiet", &refname])
+            .ok();
+
+        let outcome = match local {
+            None => {
+                repo.git(&["update-ref", &refname, sha])?;
+                if is_current {
+                    // Nothing checked out yet: fill in missing files, keep existing ones
+                    repo.git(&["reset", "--quiet"])?;
+                    clone::print_conflicts(&clone::restore_files(repo)?);
+                }
+                "created"
+            }
+            Some(local) if local == *sha || is_ancestor(repo, sha, &local) => "up to date",
+            Some(local) if is_ancestor(repo, &local, sha) || force => {
+                let fast_forward = is_ancestor(repo, &local, sha);
+                if is_current {
+                    let stray_existed = repo.work_tree.join(".side-tracked").exists();
+                    if fast_forward {
+                        repo.git(&["merge", "--ff-only", "--quiet", sha])?;
+                    } else {
+                        repo.git(&["reset", "--hard", "--quiet", sha])?;
+                    }
+                    repo.reclaim_manifest(stray_existed)?;
+                } else {
+                    repo.git(&["update-ref", &refname, sha])?;
+                }
+                if fast_forward { "updated" } else { "replaced" }
+            }
+            Some(_) => {
+                diverged.push(branch.clone());
+                "diverged, skipped"
+            }
+        };
+        println!("  {} {outcome}", branch.cyan());
+    }
+
+    if diverged.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::BundleDiverged(diverged.join(", ")))
+    }
+}
+
+/// Refs under `prefix` with the commit each points to.
+fn list_refs(repo: &SideRepo, prefix: &str) -> Result<Vec<(String, String)>> {
+    let listing = repo.git(&["for-each-ref", "--format=%(refname) %(objectname)", prefix])?;
+    Ok(listing
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(refname, sha)| (refname.to_string(), sha.to_string()))
+        .collect())
+}
+
+/// Number of commits reachable from `tips` but not from `exclude`.
+fn count_new(repo: &SideRepo, tips: &[&str], exclude: &[String]) -> Result<usize> {
+    let mut args = vec!["rev-list", "--count"];
+    args.extend_from_slice(tips);
+    if !exclude.is_empty() {
+        args.push("--not");
+        args.extend(exclude.iter().map(String::as_str));
+    }
+    Ok(repo.git(&args)?.parse().unwrap_or(0))
+}
+
+fn is_ancestor(repo: &SideRepo, ancestor: &str, descendant: &str) -> bool {
+    repo.git(&["merge-base", "--is-ancestor", ancestor, descendant])
+        .is_ok()
+}
+
+/// Prefix of the export refs; in central storage they are kept per project.
+fn export_prefix(repo: &SideRepo) -> String {
+    if repo.central.is_some() {
+        format!("{EXPORT_REF_PREFIX}{}/", repo.root_sha)
+    } else {
+        EXPORT_REF_PREFIX.to_string()
+    }
+}
+
+fn export_ref(repo: &SideRepo, branch: &str) -> String {
+    format!("{}{branch}", export_prefix(repo))
+}
+
+/// Resolve `file` against the current directory; git runs from the project root.
+fn absolute(file: &Path) -> Result<PathBuf> {
+    std::path::absolute(file).map_err(|e| Error::ReadFile {
+        path: file.to_path_buf(),
+        source: e,
+    })
+}
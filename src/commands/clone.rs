@@ -0,0 +1,233 @@
+use std::collections::BTreeSet;
+use std::fs;
+
+use colored::Colorize;
+
+use crate::error::{Error, Result};
+use crate::git;
+use crate::settings::Settings;
+use crate::side_repo::{MAIN_COMMIT_TRAILER, SideRepo};
+
+/// Bootstrap this project's side repo from an existing side remote.
+///
+/// The remote's history must link to this project through `Main-Commit` trailers
+/// (skipped with `no_verify`). Tracked files missing from the work tree are restored;
+/// existing files are never overwritten and differing ones are reported instead.
+///
+/// # Errors
+///
+/// Returns an error if a side repo already exists, the remote cannot be fetched,
+/// or it does not belong to this project.
+pub fn run(url: &str, no_verify: bool) -> Result<()> {
+    let repo = SideRepo::open()?;
+    if repo.is_initialized() {
+        return Err(Error::SideRepoExists(repo.git_dir));
+    }
+
+    let settings = Settings::load(&repo)?;
+
+    // In central storage the remote is shared and may already be set up
+    let add_remote = match central_remote_url(&repo, &settings.remote) {
+        Some(existing) if existing == url => false,
+        Some(existing) => {
+            return Err(Error::UnrelatedRemote {
+                url: url.to_string(),
+                reason: format!(
+                    "the central side repo already uses {existing} as {}",
+                    settings.remote
+                ),
+            });
+        }
+        None => true,
+    };
+
+    repo.ensure_initialized()?;
+    if let Err(e) = fetch_and_attach(&repo, &settings, url, add_remote, no_verify) {
+        // Leave nothing half-cloned behind so the clone can simply be retried
+        if add_remote && repo.central.is_some() {
+            let _ = repo.git(&["remote", "remove", &settings.remote]);
+        }
+        let _ = fs::remove_dir_all(&repo.git_dir);
+        return Err(e);
+    }
+
+    let conflicts = restore_files(&repo)?;
+
+    println!(
+        "{} Side repo cloned from {} into {}",
+        "Done.".green().bold(),
+        url.cyan(),
+        repo.central.as_ref().unwrap_or(&repo.git_dir).display()
+    );
+
+    print_conflicts(&conflicts);
+    Ok(())
+}
+
+/// List existing files that [`restore_files`] left untouched, with how to resolve them.
+pub fn print_conflicts(conflicts: &[String]) {
+    if conflicts.is_empty() {
+        return;
+    }
+
+    println!();
+    println!(
+        "{} existing file(s) differ from the side repo and were left untouched:",
+        conflicts.len().to_string().yellow()
+    );
+    for path in conflicts {
+        println!("    {}", path.yellow());
+    }
+    println!(
+        "{}",
+        "Use 'git side git checkout -- <path>' to take the side version, or commit to keep yours."
+            .dimmed()
+    );
+}
+
+/// Add `url` as the configured remote (unless already there), fetch it, check it
+/// belongs to this project and point the local side branch at it.
+fn fetch_and_attach(
+    repo: &SideRepo,
+    settings: &Settings,
+    url: &str,
+    add_remote: bool,
+    no_verify: bool,
+) -> Result<()> {
+    if add_remote {
+        repo.git(&["remote", "add", &settings.remote, url])?;
+    }
+    repo.fetch(settings)?;
+
+    let remote_ref = repo.upstream_ref(&settings.remote, &settings.branch);
+    if repo
+        .git(&["rev-parse", "--verify", "--quiet", &remote_ref])
+        .is_err()
+    {
+        return Err(Error::UnrelatedRemote {
+            url: url.to_string(),
+            reason: format!("it has no '{}' branch for this project", settings.branch),
+        });
+    }
+
+    if !no_verify {
+        verify_project(repo, url, &remote_ref)?;
+    }
+
+    let local_ref = repo.branch_ref(&settings.branch);
+    repo.git(&["update-ref", &local_ref, &remote_ref])?;
+    if repo.central.is_none() {
+        let upstream = format!("{}/{}", settings.remote, settings.branch);
+        repo.git(&["branch", "--set-upstream-to", &upstream, &settings.branch])?;
+    }
+
+    // Index matches the side HEAD; the work tree is handled separately
+    repo.git(&["reset", "--quiet"])?;
+    Ok(())
+}
+
+/// URL of the central repo's `remote`, if this project is in central storage and it is configured.
+fn central_remote_url(repo: &SideRepo, remote: &str) -> Option<String> {
+    let central = repo.central.as_ref()?;
+    git::run_bare(central, &["remote", "get-url", remote]).ok()
+}
+
+/// Check that the side history records commits of this project.
+/// A recorded main commit counts when it exists locally and descends from our root commit.
+fn verify_project(repo: &SideRepo, url: &str, remote_ref: &str) -> Result<()> {
+    let format = format!("--format=%(trailers:key={MAIN_COMMIT_TRAILER},valueonly)");
+    let log = repo.git(&["log", &format, remote_ref])?;
+    // Trailers come from the remote: only full commit SHAs may reach git as arguments
+    let recorded: BTreeSet<&str> = log
+        .lines()
+        .map(str::trim)
+        .filter(|l| is_full_sha(l))
+        .collect();
+
+    if recorded.is_empty() {
+        return Err(Error::UnrelatedRemote {
+            url: url.to_string(),
+            reason: format!(
+                "no side commit records a {MAIN_COMMIT_TRAILER} (use --no-verify if you are sure)"
+            ),
+        });
+    }
+
+    let belongs = recorded.iter().any(|sha| {
+        git::run(&[
+            "merge-base",
+            "--is-ancestor",
+            "--end-of-options",
+            &repo.root_sha,
+            sha,
+        ])
+        .is_ok()
+    });
+    if belongs {
+        return Ok(());
+    }
+
+    Err(Error::UnrelatedRemote {
+        url: url.to_string(),
+        reason: format!(
+            "none of its {} recorded main commits are part of this repository's history \
+             (fetch the main repo first, or use --no-verify)",
+            recorded.len()
+        ),
+    })
+}
+
+/// Whether `value` is a full SHA-1 or SHA-256 commit id in lowercase hex.
+fn is_full_sha(value: &str) -> bool {
+    matches!(value.len(), 40 | 64) && value.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+/// Write side files that are missing from the work tree and move the manifest into place.
+/// Returns the files that already exist with different content.
+///
+/// # Errors
+///
+/// Returns an error if the side tree cannot be read or the files cannot be written.
+pub fn restore_files(repo: &SideRepo) -> Result<Vec<String>> {
+    let listing = repo.git(&["ls-tree", "-r", "-z", "HEAD"])?;
+
+    let mut missing = Vec::new();
+    let mut conflicts = Vec::new();
+    for entry in listing.split('\0').filter(|e| !e.is_empty()) {
+        // "<mode> <type> <sha>\t<path>"
+        let Some((info, path)) = entry.split_once('\t') else {
+            continue;
+        };
+        if path == ".side-tracked" {
+            continue;
+        }
+
+        let on_disk = repo.work_tree.join(path);
+        if !on_disk.exists() {
+            missing.push(path.to_string());
+            continue;
+        }
+
+        let side_sha = info.split_whitespace().nth(2).unwrap_or_default();
+        let disk_sha = repo
+            .git(&["hash-object", "--", path])
+            .unwrap_or_default();
+        if disk_sha != side_sha {
+            conflicts.push(path.to_string());
+        }
+    }
+
+    if !missing.is_empty() {
+        let mut args = vec!["checkout-index", "--"];
+        args.extend(missing.iter().map(String::as_str));
+        repo.git(&args)?;
+        println!("Restored {} file(s).", missing.len().to_string().cyan());
+    }
+
+    if let Ok(manifest) = repo.git(&["show", "HEAD:.side-tracked"]) {
+        let path = repo.tracked_file();
+        fs::write(&path, manifest).map_err(|e| Error::WriteFile { path, source: e })?;
+    }
+
+    Ok(conflicts)
+}
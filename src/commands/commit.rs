@@ -1,21 +1,98 @@
+use std::path::PathBuf;
+
 use colored::Colorize;
 
-use crate::error::Result;
-use crate::side_repo::SideRepo;
+use crate::error::{Error, Result};
+use crate::message::{self, MessageContext};
+use crate::settings::Settings;
+use crate::side_repo::{CommitOptions, SideRepo};
+use crate::tracked::{self, TrackedPaths};
+
+/// Where the commit message comes from.
+pub enum MessageSource {
+    /// Message given on the command line.
+    Literal(String),
+    /// Render a template; `None` uses the project's configured template.
+    Template(Option<String>),
+    /// Open the user's editor (`$GIT_EDITOR`, `core.editor`, ...).
+    Editor,
+}
 
-/// Commit staged changes to side repo.
+/// Commit changes to side repo.
+///
+/// With `all`, every tracked path is staged first (like `auto`). With `paths`,
+/// only those tracked paths are committed, using their current work tree state.
 ///
 /// # Errors
 ///
-/// Returns an error if there's nothing to commit or if the commit fails.
-pub fn run(message: &str) -> Result<()> {
+/// Returns an error if a path is not tracked, there's nothing to commit, or the commit fails.
+pub fn run(source: MessageSource, paths: &[PathBuf], all: bool, amend: bool) -> Result<()> {
     let repo = SideRepo::open()?;
     repo.ensure_initialized()?;
 
-    // Always stage .side-tracked to ensure it's included
-    repo.stage_tracked_file()?;
+    let tracked = TrackedPaths::load(&repo)?;
+
+    // Only tracked paths may be committed
+    let paths: Vec<PathBuf> = paths
+        .iter()
+        .map(|p| tracked::normalize(&repo.work_tree, p))
+        .collect();
+    if let Some(untracked) = paths.iter().find(|p| !tracked.covers(p)) {
+        return Err(Error::PathNotTracked(untracked.clone()));
+    }
+
+    // Files over side.maxFileSize are left out, as with `auto`
+    let max_file_size = Settings::load(&repo)?.max_file_size;
+    let skipped = if all {
+        if tracked.is_empty() {
+            return Err(Error::NoTrackedPaths);
+        }
+        let tracked_paths: Vec<_> = tracked.paths().iter().cloned().collect();
+        repo.stage_paths(&tracked_paths, max_file_size)?
+    } else if paths.is_empty() {
+        Vec::new()
+    } else {
+        repo.stage_paths(&paths, max_file_size)?
+    };
+    if let Some(limit) = max_file_size {
+        for (path, size) in skipped {
+            eprintln!(
+                "{} {} ({size} bytes, side.maxFileSize is {limit})",
+                "Skipped large file:".yellow(),
+                path.display()
+            );
+        }
+    }
+
+    // Always stage .side-tracked to ensure it's included (unless limiting to paths)
+    if paths.is_empty() {
+        repo.stage_tracked_file()?;
+    }
+
+    let message = match source {
+        MessageSource::Literal(message) => Some(message),
+        MessageSource::Template(template) => {
+            let template = template
+                .or_else(|| message::configured_template(&repo))
+                .unwrap_or_else(|| message::DEFAULT_TEMPLATE.to_string());
+            let rendered = MessageContext::gather(&repo)?.render(&template);
+            if rendered.trim().is_empty() {
+                return Err(Error::EmptyMessage(template));
+            }
+            Some(rendered)
+        }
+        MessageSource::Editor => None,
+    };
+
+    if message.as_ref().is_some_and(|m| m.trim().is_empty()) {
+        return Err(Error::GitCommandFailed("empty commit message".to_string()));
+    }
 
-    repo.commit(message)?;
+    repo.commit_with(&CommitOptions {
+        message: message.as_deref(),
+        paths: &paths,
+        amend,
+    })?;
 
     println!("{}", "Committed to side repo.".green().bold());
     Ok(())
@@ -0,0 +1,65 @@
+use std::io::{self, Write};
+
+use clap_complete::Shell;
+use clap_complete::env::Shells;
+
+use crate::complete::COMPLETE_VAR;
+use crate::error::{Error, Result};
+
+/// Name of the binary the completions are registered for.
+const BIN: &str = "git-side";
+
+/// Bash: git's completion calls `_git_side` for `git side ...`; re-shape the words
+/// as if `git-side` had been typed directly.
+const BASH_GIT_SHIM: &str = r#"
+_git_side() {
+    local COMP_WORDS=("git-side" "${COMP_WORDS[@]:2}")
+    local COMP_CWORD=$((COMP_CWORD - 1))
+    _clap_complete_git_side "git-side" "${COMP_WORDS[COMP_CWORD]}"
+}
+"#;
+
+/// Zsh: git's completion calls `_git-side` with `side` as the first word.
+const ZSH_GIT_SHIM: &str = r#"
+_git-side() {
+    words=("git-side" "${(@)words[2,-1]}")
+    _clap_dynamic_completer_git_side
+}
+"#;
+
+/// Fish: complete `git side ...` by forwarding the tokens after `side`.
+const FISH_GIT_SHIM: &str = r#"
+complete --keep-order --exclusive --command git --condition "__fish_seen_subcommand_from side" --arguments "(COMPLETE=fish git-side -- git-side (commandline --current-process --tokenize --cut-at-cursor)[3..] (commandline --current-token))"
+"#;
+
+/// Print the shell completion script for `shell`.
+///
+/// Completions are dynamic: the script calls back into git-side, so tracked paths,
+/// ignored files and hook names are always current. Both `git-side` and `git side`
+/// are covered for bash, zsh and fish.
+///
+/// # Errors
+///
+/// Returns an error if the script cannot be written to stdout.
+pub fn run(shell: Shell) -> Result<()> {
+    let name = shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&name)
+        .ok_or(Error::UnsupportedShell(name))?;
+
+    let mut buf = Vec::new();
+    completer
+        .write_registration(COMPLETE_VAR, BIN, BIN, BIN, &mut buf)
+        .map_err(Error::WriteCompletions)?;
+
+    let shim = match shell {
+        Shell::Bash => BASH_GIT_SHIM,
+        Shell::Zsh => ZSH_GIT_SHIM,
+        Shell::Fish => FISH_GIT_SHIM,
+        _ => "",
+    };
+    buf.extend_from_slice(shim.as_bytes());
+
+    io::stdout().write_all(&buf).map_err(Error::WriteCompletions)
+}
@@ -0,0 +1,15 @@
+use crate::error::Result;
+use crate::side_repo::SideRepo;
+
+/// Run an arbitrary git command against the side repo, attached to the terminal.
+///
+/// # Errors
+///
+/// Returns an error if the side repo cannot be opened or git exits with non-zero status.
+pub fn run(args: &[String]) -> Result<()> {
+    let repo = SideRepo::open()?;
+    repo.ensure_initialized()?;
+
+    let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    repo.stream(&args_refs)
+}
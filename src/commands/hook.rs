@@ -1,29 +1,187 @@
+use std::fmt;
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use clap::ValueEnum;
 use colored::Colorize;
 
+use crate::background;
+use crate::commands;
 use crate::error::{Error, Result};
 use crate::git;
+use crate::settings::Settings;
+use crate::side_repo::SideRepo;
+use crate::tracked::{PathStatus, TrackedPaths};
 
 const HOOK_MARKER_START: &str = "# >>> git-side auto >>>";
 const HOOK_MARKER_END: &str = "# <<< git-side auto <<<";
-const HOOK_CONTENT: &str = r"
-# Auto-sync side-tracked files
-git side auto
-";
 
-/// Get the path to a git hook.
-fn hook_path(hook_name: &str) -> Result<PathBuf> {
-    let git_dir = git::git_dir()?;
-    Ok(git_dir.join("hooks").join(hook_name))
+/// Git hooks git-side knows how to drive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum HookKind {
+    /// Commit side-tracked changes after every main commit
+    PostCommit,
+    /// Pull the side repo after a merge or pull
+    PostMerge,
+    /// Restore side files matching the checked-out branch
+    PostCheckout,
+    /// Push the side repo before the main repo is pushed
+    PrePush,
+}
+
+impl HookKind {
+    /// Every supported hook, in display order.
+    pub const ALL: [Self; 4] = [
+        Self::PostCommit,
+        Self::PostMerge,
+        Self::PostCheckout,
+        Self::PrePush,
+    ];
+
+    /// The hook file name as Git knows it.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::PostCommit => "post-commit",
+            Self::PostMerge => "post-merge",
+            Self::PostCheckout => "post-checkout",
+            Self::PrePush => "pre-push",
+        }
+    }
+
+    /// Short description of what the hook does.
+    #[must_use]
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::PostCommit => "auto-commit side-tracked files",
+            Self::PostMerge => "fast-forward side repo from remote",
+            Self::PostCheckout => "restore side files for the new branch",
+            Self::PrePush => "push side repo to remote",
+        }
+    }
+
+    /// Whether the hook's action is deferred to a detached process.
+    /// `post-checkout` runs inline since the work tree must be restored before the user continues.
+    #[must_use]
+    pub const fn runs_in_background(self) -> bool {
+        !matches!(self, Self::PostCheckout)
+    }
+
+    /// The shell command placed in the hook file.
+    fn command(self) -> String {
+        format!("git side hook run {} \"$@\"", self.name())
+    }
+
+    /// The full section written between our markers.
+    fn content(self) -> String {
+        format!("\n# git-side: {}\n{}\n", self.description(), self.command())
+    }
+}
+
+impl fmt::Display for HookKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Third-party tools that own the hooks directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HookManager {
+    Husky,
+    Lefthook,
+    PreCommit,
+}
+
+impl HookManager {
+    /// Detect a hook manager that owns the hooks directory or the hook file at `hook_file`.
+    ///
+    /// husky points `core.hooksPath` into `.husky`; lefthook and pre-commit write
+    /// their own files into the regular hooks directory, so only those are theirs.
+    fn detect(hooks_dir: &Path, hook_file: &Path) -> Option<Self> {
+        if hooks_dir.components().any(|c| c.as_os_str() == ".husky") {
+            return Some(Self::Husky);
+        }
+
+        let content = fs::read_to_string(hook_file).ok()?;
+        if content.contains("husky") {
+            Some(Self::Husky)
+        } else if content.contains("lefthook") {
+            Some(Self::Lefthook)
+        } else if content.contains("File generated by pre-commit") {
+            Some(Self::PreCommit)
+        } else {
+            None
+        }
+    }
+
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Husky => "husky",
+            Self::Lefthook => "lefthook",
+            Self::PreCommit => "pre-commit",
+        }
+    }
+
+    /// Exact steps to wire `command` into the manager's own config.
+    fn instructions(self, kind: HookKind) -> String {
+        let hook_name = kind.name();
+        match self {
+            Self::Husky => format!(
+                "Add this line to .husky/{hook_name}:\n\n    {}\n",
+                kind.command()
+            ),
+            Self::Lefthook => format!(
+                "Add this to lefthook-local.yml (or lefthook.yml), then run `lefthook install`:\n\n\
+                 {hook_name}:\n  commands:\n    git-side:\n      run: git side hook run {hook_name} {{0}}\n"
+            ),
+            Self::PreCommit => format!(
+                "Add this to .pre-commit-config.yaml, then run `pre-commit install --hook-type {hook_name}`:\n\n\
+                 - repo: local\n  hooks:\n    - id: git-side\n      name: git-side\n      \
+                 entry: git side hook run {hook_name}\n      language: system\n      always_run: true\n      \
+                 pass_filenames: false\n      stages: [{hook_name}]\n"
+            ),
+        }
+    }
+}
+
+/// Get the path to a git hook, as Git will resolve it.
+fn hook_path(kind: HookKind) -> Result<PathBuf> {
+    git::hook_path(kind.name())
+}
+
+/// Check that Git will actually execute the hook we just wrote.
+fn verify(kind: HookKind, path: &Path) -> Result<()> {
+    let inactive = |reason: &str| Error::HookInactive {
+        hook: kind.to_string(),
+        reason: reason.to_string(),
+    };
+
+    if !is_installed(kind)? {
+        return Err(inactive("hook file does not contain the git-side section"));
+    }
+
+    #[cfg(unix)]
+    {
+        let mode = fs::metadata(path)
+            .map_err(|e| Error::ReadFile {
+                path: path.to_path_buf(),
+                source: e,
+            })?
+            .permissions()
+            .mode();
+        if mode & 0o111 == 0 {
+            return Err(inactive("hook file is not executable"));
+        }
+    }
+
+    Ok(())
 }
 
 /// Check if our hook is already installed.
-fn is_installed(hook_name: &str) -> Result<bool> {
-    let path = hook_path(hook_name)?;
+fn is_installed(kind: HookKind) -> Result<bool> {
+    let path = hook_path(kind)?;
     if !path.exists() {
         return Ok(false);
     }
@@ -41,12 +199,26 @@ fn is_installed(hook_name: &str) -> Result<bool> {
 /// # Errors
 ///
 /// Returns an error if the hook is already installed or if file operations fail.
-pub fn install(hook_name: &str) -> Result<()> {
-    if is_installed(hook_name)? {
-        return Err(Error::HookAlreadyInstalled(hook_name.to_string()));
+pub fn install(kind: HookKind) -> Result<()> {
+    if is_installed(kind)? {
+        return Err(Error::HookAlreadyInstalled(kind.to_string()));
     }
 
-    let path = hook_path(hook_name)?;
+    let path = hook_path(kind)?;
+
+    // Hook managers regenerate the hooks they own — writing there would be lost
+    let hooks_dir = git::hooks_dir()?;
+    if let Some(manager) = HookManager::detect(&hooks_dir, &path) {
+        println!(
+            "{} hooks are managed by {}; not writing {}",
+            "Skipped.".yellow().bold(),
+            manager.name().cyan(),
+            path.display()
+        );
+        println!();
+        print!("{}", manager.instructions(kind));
+        return Ok(());
+    }
 
     // Ensure hooks directory exists
     if let Some(parent) = path.parent() {
@@ -68,7 +240,8 @@ pub fn install(hook_name: &str) -> Result<()> {
 
     // Append our hook
     let new_content = format!(
-        "{existing}\n{HOOK_MARKER_START}{HOOK_CONTENT}{HOOK_MARKER_END}\n"
+        "{existing}\n{HOOK_MARKER_START}{}{HOOK_MARKER_END}\n",
+        kind.content()
     );
 
     fs::write(&path, new_content).map_err(|e| Error::WriteFile {
@@ -92,10 +265,13 @@ pub fn install(hook_name: &str) -> Result<()> {
         })?;
     }
 
+    verify(kind, &path)?;
+
     println!(
-        "{} {} hook installed",
+        "{} {} hook installed at {}",
         "Done.".green().bold(),
-        hook_name.cyan()
+        kind.name().cyan(),
+        path.display()
     );
 
     Ok(())
@@ -106,12 +282,12 @@ pub fn install(hook_name: &str) -> Result<()> {
 /// # Errors
 ///
 /// Returns an error if the hook is not installed or if file operations fail.
-pub fn uninstall(hook_name: &str) -> Result<()> {
-    if !is_installed(hook_name)? {
-        return Err(Error::HookNotInstalled(hook_name.to_string()));
+pub fn uninstall(kind: HookKind) -> Result<()> {
+    if !is_installed(kind)? {
+        return Err(Error::HookNotInstalled(kind.to_string()));
     }
 
-    let path = hook_path(hook_name)?;
+    let path = hook_path(kind)?;
 
     let content = fs::read_to_string(&path).map_err(|e| Error::ReadFile {
         path: path.clone(),
@@ -156,8 +332,225 @@ pub fn uninstall(hook_name: &str) -> Result<()> {
     println!(
         "{} {} hook removed",
         "Done.".green().bold(),
-        hook_name.cyan()
+        kind.name().cyan()
     );
 
     Ok(())
 }
+
+/// List supported hooks and where they are installed.
+///
+/// # Errors
+///
+/// Returns an error if not in a git repository or if a hook file cannot be read.
+pub fn list() -> Result<()> {
+    let hooks_dir = git::hooks_dir()?;
+
+    println!("Hooks directory: {}", hooks_dir.display());
+    let mut manager = None;
+    for kind in HookKind::ALL {
+        manager = manager.or(HookManager::detect(&hooks_dir, &hook_path(kind)?));
+    }
+    if let Some(manager) = manager {
+        println!("Managed by: {}", manager.name().cyan());
+    }
+    println!();
+
+    for kind in HookKind::ALL {
+        let status = if is_installed(kind)? {
+            "installed".green()
+        } else {
+            "-".dimmed()
+        };
+        println!("  {:<14} {:<10} {}", kind.name(), status, kind.description());
+    }
+
+    Ok(())
+}
+
+/// Entry point for installed hooks: perform the action for `kind`.
+/// `args` are the arguments Git passed to the hook.
+///
+/// Network-bound hooks are re-run detached so the main Git command never waits on them.
+/// Failures are recorded in the run log and reported as a one-line warning; they never
+/// fail the hook, so the main Git operation is never blocked.
+///
+/// # Errors
+///
+/// Only returns an error if the side repo cannot be located.
+pub fn run(kind: HookKind, args: &[String], detached: bool) -> Result<()> {
+    let repo = SideRepo::open()?;
+    if !repo.is_initialized() {
+        return Ok(());
+    }
+
+    let label = format!("hook {kind}");
+    let result = if detached {
+        colored::control::set_override(false);
+        background::record(&repo, &label, || perform(&repo, kind, args))
+    } else if kind.runs_in_background() {
+        let mut spawn_args = vec!["hook", "run", kind.name(), "--detached", "--"];
+        spawn_args.extend(args.iter().map(String::as_str));
+        background::spawn(&repo, &spawn_args)
+    } else {
+        background::record(&repo, &label, || perform(&repo, kind, args))
+    };
+
+    if let Err(e) = result
+        && !detached
+    {
+        eprintln!(
+            "{} {} {e} {}",
+            "[git-side]".dimmed(),
+            format!("{kind} hook failed:").yellow(),
+            "(see 'git side hook log')".dimmed()
+        );
+    }
+    Ok(())
+}
+
+/// Perform the action for `kind` in the foreground.
+fn perform(repo: &SideRepo, kind: HookKind, args: &[String]) -> Result<()> {
+    let settings = Settings::load(repo)?;
+    match kind {
+        HookKind::PostCommit => commands::auto::sync(repo, false, false),
+        HookKind::PostMerge if repo.has_remote(&settings) => post_merge(repo, &settings),
+        HookKind::PrePush if !repo.push_targets(&settings).is_empty() => {
+            commands::push::run(false)
+        }
+        HookKind::PostMerge | HookKind::PrePush => Ok(()),
+        HookKind::PostCheckout => post_checkout(repo, args),
+    }
+}
+
+/// Bring in side commits pushed from elsewhere after a merge.
+///
+/// Unlike `git side pull`, local side work always survives: the hook does nothing while
+/// tracked files have uncommitted side changes, and only fast-forwards.
+fn post_merge(repo: &SideRepo, settings: &Settings) -> Result<()> {
+    let tracked = TrackedPaths::load(repo)?;
+    if tracked
+        .status(repo, settings.max_file_size)?
+        .iter()
+        .any(PathStatus::has_changes)
+    {
+        return Err(Error::SideChangesPending);
+    }
+
+    let message = if repo.fast_forward(settings)? {
+        "Fast-forwarded side repo from"
+    } else {
+        "Side repo already up to date with"
+    };
+    println!(
+        "{} {} {}",
+        "[git-side]".dimmed(),
+        message.green(),
+        settings.remote.cyan()
+    );
+    Ok(())
+}
+
+/// Show recent hook and background runs from the run log.
+///
+/// # Errors
+///
+/// Returns an error if the side repo cannot be opened or the log cannot be read.
+pub fn log(count: usize, failures_only: bool) -> Result<()> {
+    let repo = SideRepo::open()?;
+    let entries = background::entries(&repo)?;
+
+    let selected: Vec<_> = entries
+        .iter()
+        .filter(|e| !failures_only || e.failed())
+        .collect();
+
+    if selected.is_empty() {
+        println!("{}", "No runs recorded.".yellow());
+        return Ok(());
+    }
+
+    let skip = selected.len().saturating_sub(count);
+    for entry in &selected[skip..] {
+        let status = match &entry.outcome {
+            Some(Ok(())) => "ok".green().bold(),
+            Some(Err(_)) => "failed".red().bold(),
+            None => "incomplete".yellow().bold(),
+        };
+        println!("{} {} {}", entry.started.dimmed(), entry.label.cyan(), status);
+        for line in &entry.output {
+            println!("    {line}");
+        }
+        if let Some(Err(message)) = &entry.outcome {
+            println!("    {}", message.red());
+        }
+    }
+
+    Ok(())
+}
+
+/// Switch the side repo to the branch matching the main repo.
+/// Outside per-branch mode this only happens if the side repo already has such a branch.
+/// Pending side changes are committed to the branch being left so nothing is lost.
+fn post_checkout(repo: &SideRepo, args: &[String]) -> Result<()> {
+    // Third argument is 1 for branch checkouts, 0 for file checkouts
+    if args.get(2).is_some_and(|flag| flag == "0") {
+        return Ok(());
+    }
+
+    let per_branch = repo.per_branch()?;
+    let branch = if per_branch {
+        repo.branch_for_main()?
+    } else {
+        match git::current_branch()? {
+            Some(branch) if repo.has_branch(&branch) => branch,
+            _ => return Ok(()),
+        }
+    };
+
+    let previous = repo.current_branch()?;
+    if previous.as_deref() == Some(branch.as_str()) {
+        return Ok(());
+    }
+
+    // Save pending side changes on the branch we are leaving
+    if let Some(previous) = &previous
+        && repo.has_branch(previous)
+        && let Ok(tracked) = TrackedPaths::load(repo)
+        && !tracked.is_empty()
+    {
+        let paths: Vec<_> = tracked.paths().iter().cloned().collect();
+        let max_file_size = Settings::load(repo)?.max_file_size;
+        let skipped = repo.stage_paths(&paths, max_file_size)?;
+        if let Some(limit) = max_file_size {
+            for (path, size) in skipped {
+                eprintln!(
+                    "{} {} {} ({size} bytes, side.maxFileSize is {limit})",
+                    "[git-side]".dimmed(),
+                    "warning: skipped large file".yellow(),
+                    path.display()
+                );
+            }
+        }
+        repo.stage_tracked_file()?;
+        match repo.commit(&format!("Save side state before switching to {branch}")) {
+            Ok(()) | Err(Error::NothingToCommit) => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    if repo.has_branch(&branch) {
+        repo.checkout(&branch)?;
+    } else {
+        // New main branch: start its side branch from the current side state
+        repo.attach_branch(&branch)?;
+    }
+
+    println!(
+        "{} {} {}",
+        "[git-side]".dimmed(),
+        "Switched side repo to".green().bold(),
+        branch.cyan()
+    );
+    Ok(())
+}
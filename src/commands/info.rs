@@ -1,6 +1,7 @@
 use colored::Colorize;
 
-use crate::error::Result;
+use crate::commands::status;
+use crate::error::{Error, Result};
 use crate::side_repo::SideRepo;
 use crate::tracked::TrackedPaths;
 
@@ -26,22 +27,53 @@ pub fn run() -> Result<()> {
     println!();
 
     // Show project-specific info if in a git repo
-    if let Ok(repo) = SideRepo::open() {
-        println!("{}", "Current project:".cyan());
-        println!("  Root SHA: {}", repo.root_sha);
+    let repo = match SideRepo::open() {
+        Ok(repo) => repo,
+        Err(e @ Error::ProjectMismatch { .. }) => {
+            println!("{}", "Current project:".cyan());
+            println!("  {}", e.to_string().red());
+            return Ok(());
+        }
+        Err(_) => return Ok(()),
+    };
+
+    println!("{}", "Current project:".cyan());
+    println!("  Root SHA: {}", repo.root_sha);
+    if let Some(central) = &repo.central {
+        println!("  Side repo: {} (central storage)", central.display());
+    } else {
         println!("  Side repo: {}", repo.git_dir.display());
-        println!("  Initialized: {}", if repo.is_initialized() { "yes".green() } else { "no".yellow() });
-
-        if repo.is_initialized()
-            && let Ok(tracked) = TrackedPaths::load(&repo)
-        {
-            let paths: Vec<_> = tracked.paths().iter().collect();
-            if paths.is_empty() {
-                println!("  Tracked paths: {}", "none".yellow());
-            } else {
-                println!("  Tracked paths:");
-                for path in paths {
+    }
+    println!("  Initialized: {}", if repo.is_initialized() { "yes".green() } else { "no".yellow() });
+
+    let identity = repo.identity();
+    if let Some(path) = &identity.path
+        && *path != repo.work_tree
+    {
+        println!("  Created for: {}", path.display());
+    }
+    if let (Some(at), Some(with)) = (&identity.created_at, &identity.created_with) {
+        println!("  Created: {at} (git-side {with})");
+    }
+
+    let pushes = repo.last_pushes();
+    if !pushes.is_empty() {
+        status::print_last_pushes(&pushes, "  ");
+    }
+
+    if repo.is_initialized()
+        && let Ok(tracked) = TrackedPaths::load(&repo)
+    {
+        let paths: Vec<_> = tracked.paths().iter().collect();
+        if paths.is_empty() {
+            println!("  Tracked paths: {}", "none".yellow());
+        } else {
+            println!("  Tracked paths:");
+            for path in paths {
+                if repo.work_tree.join(path).exists() {
                     println!("    - {}", path.display());
+                } else {
+                    println!("    - {} {}", path.display(), "(missing)".red());
                 }
             }
         }
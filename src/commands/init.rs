@@ -1,23 +1,40 @@
-use std::path::Path;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use colored::Colorize;
 
 use crate::config;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::git;
+use crate::message;
+use crate::settings::Settings;
+use crate::side_repo::SideRepo;
 
 /// Initialize side repo with optional custom path.
 ///
+/// `central` moves the project into the shared multi-project repo, `relocate`
+/// moves a side repo left in the platform directory to the configured base path,
+/// `per_branch` toggles per-branch mode and `template` sets the auto-commit
+/// message template when given (an empty template resets to the default).
+///
 /// # Errors
 ///
 /// Returns an error if not in a git repo or if config cannot be written.
-pub fn run(path: Option<&Path>) -> Result<()> {
+pub fn run(
+    path: Option<&Path>,
+    central: bool,
+    relocate: bool,
+    per_branch: Option<bool>,
+    template: Option<&str>,
+) -> Result<()> {
     // Get the project identifier
     let work_tree = git::repo_root()?;
     let path_hash = config::hash_path(&work_tree);
 
     // Get or resolve root SHA
-    let root_sha = if let Some(sha) = config::cache_lookup(&path_hash)? {
+    let root_sha = if let Some(sha) = config::cache_lookup(&path_hash)?
+        && git::has_commit(&sha)
+    {
         sha
     } else {
         let sha = git::initial_commit_sha()?;
@@ -25,23 +42,254 @@ pub fn run(path: Option<&Path>) -> Result<()> {
         sha
     };
 
-    // Store custom path if provided
-    if let Some(base_path) = path {
-        config::paths_store(&root_sha, base_path)?;
+    // Before opening: opening refuses to run while the repo is stranded
+    if relocate {
+        let base = config::configured_base_path()?;
+        if let Some(found) = config::stranded_repo(&root_sha, &base) {
+            let target = base.join(&root_sha);
+            move_storage(&found, &target, || Ok(()))?;
+            println!(
+                "{} Side repo moved to: {}",
+                "Moved.".green().bold(),
+                target.display()
+            );
+        } else {
+            println!("No side repo to move: it is already where git-side looks for it.");
+        }
+    }
+
+    let current = SideRepo::open()?;
+
+    if central {
+        if let Some(shared) = &current.central {
+            println!("Already in central storage: {}", shared.display());
+        } else {
+            let shared = config::default_central_repo()?;
+            migrate_to_central(&current, &shared)?;
+            println!(
+                "{} Side repo stored in the central repo: {}",
+                "Initialized.".green().bold(),
+                shared.display()
+            );
+        }
+    } else if let Some(base_path) = path {
+        // Store custom path if provided, bringing an existing side repo along
+        if current.central.is_some() {
+            return Err(Error::CentralUnsupported("a custom base path (--path)"));
+        }
+        let base_path = std::path::absolute(base_path).map_err(|e| Error::CreateDir {
+            path: base_path.to_path_buf(),
+            source: e,
+        })?;
+        let target = base_path.join(&root_sha);
 
+        if current.is_initialized() && current.git_dir != target {
+            move_storage(&current.git_dir, &target, || {
+                config::paths_store(&root_sha, &base_path)
+            })?;
+            println!(
+                "{} Side repo moved to: {}",
+                "Moved.".green().bold(),
+                target.display()
+            );
+        } else {
+            config::paths_store(&root_sha, &base_path)?;
+            println!(
+                "{} Side repo will be stored at: {}",
+                "Initialized.".green().bold(),
+                target.display()
+            );
+        }
+    } else if !relocate {
+        let location = current.central.as_ref().unwrap_or(&current.git_dir);
         println!(
             "{} Side repo will be stored at: {}",
             "Initialized.".green().bold(),
-            base_path.join(&root_sha).display()
+            location.display()
         );
-    } else {
-        let default_path = config::default_base_path().join(&root_sha);
+    }
+
+    if let Some(enabled) = per_branch {
+        let repo = SideRepo::open()?;
+        repo.config_set("side.perBranch", if enabled { "true" } else { "false" })?;
         println!(
-            "{} Side repo will be stored at: {}",
-            "Initialized.".green().bold(),
-            default_path.display()
+            "Per-branch mode: {}",
+            if enabled { "on".green() } else { "off".yellow() }
+        );
+    }
+
+    if let Some(template) = template {
+        let repo = SideRepo::open()?;
+        if template.is_empty() {
+            repo.config_unset(message::TEMPLATE_KEY);
+            println!("Message template: {}", message::DEFAULT_TEMPLATE.cyan());
+        } else {
+            repo.config_set(message::TEMPLATE_KEY, template)?;
+            println!("Message template: {}", template.cyan());
+        }
+    }
+
+    Ok(())
+}
+
+/// Move a project's own side repo into the shared repo at `central`.
+///
+/// Branches, settings and the `.side-tracked` manifest are copied over before the
+/// project is recorded as centrally stored; on failure everything copied is removed
+/// again and the project keeps using its own repo.
+fn migrate_to_central(current: &SideRepo, central: &Path) -> Result<()> {
+    let target = SideRepo::in_central(
+        current.work_tree.clone(),
+        current.root_sha.clone(),
+        central.to_path_buf(),
+    );
+    if target.is_initialized() {
+        return Err(Error::SideRepoExists(target.git_dir));
+    }
+    if !current.is_initialized() {
+        return config::central_store(&current.root_sha, central);
+    }
+
+    target.ensure_initialized()?;
+    if let Err(e) = copy_into_central(current, &target)
+        .and_then(|()| config::central_store(&current.root_sha, central))
+    {
+        remove_from_central(&target);
+        return Err(e);
+    }
+
+    if !current.remotes().is_empty() {
+        println!(
+            "{}",
+            "Note: remotes are not carried over; the central repo's remotes ('git side remote') are shared by all projects."
+                .dimmed()
+        );
+    }
+
+    // The project now lives in the central repo; a leftover copy is only wasted space
+    if let Err(e) = fs::remove_dir_all(&current.git_dir) {
+        eprintln!(
+            "{} could not remove old side repo at {}: {e}",
+            "warning:".yellow().bold(),
+            current.git_dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// Copy branches, `side.*` settings, the manifest and the checked-out branch.
+fn copy_into_central(from: &SideRepo, to: &SideRepo) -> Result<()> {
+    let from_dir = from.git_dir.to_string_lossy();
+    let refspec = format!("+refs/heads/*:{}*", to.ref_prefix());
+    to.git(&["fetch", "--quiet", "--no-tags", &from_dir, &refspec])?;
+
+    // Settings and identity (creation time included)
+    let settings = from
+        .git(&["config", "--local", "--get-regexp", r"^side\."])
+        .unwrap_or_default();
+    for (key, value) in settings.lines().filter_map(|l| l.split_once(' ')) {
+        to.config_set(key, value)?;
+    }
+
+    if from.tracked_file().exists() {
+        fs::copy(from.tracked_file(), to.tracked_file()).map_err(|e| Error::WriteFile {
+            path: to.tracked_file(),
+            source: e,
+        })?;
+    }
+
+    let branch = match from.current_branch()? {
+        Some(branch) => branch,
+        None => Settings::load(from)?.branch,
+    };
+    to.git(&["symbolic-ref", "HEAD", &to.branch_ref(&branch)])?;
+    if to.has_branch(&branch) {
+        to.git(&["reset", "--quiet"])?;
+    }
+    Ok(())
+}
+
+/// Drop a project's refs and directory from the shared repo.
+fn remove_from_central(repo: &SideRepo) {
+    let prefix = repo.ref_prefix();
+    let refs = repo
+        .git(&["for-each-ref", "--format=%(refname)", &prefix])
+        .unwrap_or_default();
+    for refname in refs.lines() {
+        let _ = repo.git(&["update-ref", "-d", refname]);
+    }
+    let _ = fs::remove_dir_all(&repo.git_dir);
+}
+
+/// Move a side repo to `to`, then run `commit` (which records the new location).
+///
+/// A plain rename is used when possible; across filesystems the repo is copied to a
+/// temporary directory next to `to` and renamed into place. If anything fails, the
+/// repo is left at `from` and no new location is recorded.
+fn move_storage(from: &Path, to: &Path, commit: impl FnOnce() -> Result<()>) -> Result<()> {
+    if to.exists() {
+        return Err(Error::SideRepoExists(to.to_path_buf()));
+    }
+    let move_error = |source| Error::MoveRepo {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+        source,
+    };
+
+    let parent = to.parent().unwrap_or(to);
+    fs::create_dir_all(parent).map_err(|e| Error::CreateDir {
+        path: parent.to_path_buf(),
+        source: e,
+    })?;
+
+    if fs::rename(from, to).is_ok() {
+        return commit().inspect_err(|_| {
+            let _ = fs::rename(to, from);
+        });
+    }
+
+    // Different filesystem: copy next to the target, then swap in with a rename
+    let staging = staging_path(to);
+    if let Err(e) = copy_dir(from, &staging).and_then(|()| fs::rename(&staging, to)) {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(move_error(e));
+    }
+    if let Err(e) = commit() {
+        let _ = fs::remove_dir_all(to);
+        return Err(e);
+    }
+
+    // The move is complete; a leftover old copy is only wasted space
+    if let Err(e) = fs::remove_dir_all(from) {
+        eprintln!(
+            "{} could not remove old side repo at {}: {e}",
+            "warning:".yellow().bold(),
+            from.display()
         );
     }
+    Ok(())
+}
 
+/// Temporary sibling of `target` used while copying.
+fn staging_path(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{name}.moving-{}", std::process::id()))
+}
+
+/// Recursively copy a directory tree.
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,65 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use colored::Colorize;
+
+use crate::error::{Error, Result};
+
+/// Default man page directory: `~/.local/share/man/man1`, which `man` searches
+/// automatically when `~/.local/bin` is on `$PATH`.
+fn default_man_dir() -> Result<PathBuf> {
+    dirs::home_dir()
+        .map(|home| home.join(".local/share/man/man1"))
+        .ok_or(Error::NoHomeDir)
+}
+
+/// Write the page for `cmd` and, depth first, its visible subcommands.
+/// Returns the number of pages written.
+fn write_pages(cmd: clap::Command, dir: &Path) -> Result<usize> {
+    let mut count = 0;
+    for sub in cmd.get_subcommands().filter(|s| !s.is_hide_set()).cloned() {
+        count += write_pages(sub, dir)?;
+    }
+
+    let man = clap_mangen::Man::new(cmd);
+    let path = dir.join(man.get_filename());
+    man.generate_to(dir)
+        .map_err(|e| Error::WriteFile { path, source: e })?;
+    Ok(count + 1)
+}
+
+/// Generate man pages for `cmd` and every visible subcommand (`git-side.1`,
+/// `git-side-add.1`, ...) so `git help side` and `git side --help` work.
+///
+/// # Errors
+///
+/// Returns an error if the directory cannot be created or a page cannot be written.
+pub fn run(cmd: clap::Command, dir: Option<&Path>) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => default_man_dir()?,
+    };
+
+    fs::create_dir_all(&dir).map_err(|e| Error::CreateDir {
+        path: dir.clone(),
+        source: e,
+    })?;
+
+    // Same tree clap_mangen::generate_to walks, counting what this run writes
+    let mut cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+    let count = write_pages(cmd, &dir)?;
+
+    println!(
+        "{} {} man page(s) written to {}",
+        "Done.".green().bold(),
+        count.to_string().cyan(),
+        dir.display()
+    );
+    println!(
+        "If 'git help side' cannot find them, add {} to $MANPATH.",
+        dir.parent().unwrap_or(&dir).display()
+    );
+
+    Ok(())
+}
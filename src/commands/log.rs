@@ -1,15 +1,42 @@
-use crate::error::Result;
+use colored::Colorize;
+
+use crate::error::{Error, Result};
 use crate::side_repo::SideRepo;
 
 /// Show side repo history.
+/// With `main_rev`, history starts at the side snapshot closest to that main repo revision.
 ///
 /// # Errors
 ///
 /// Returns an error if the side repo cannot be opened or log command fails.
-pub fn run(args: &[String]) -> Result<()> {
+pub fn run(main_rev: Option<&str>, args: &[String]) -> Result<()> {
     let repo = SideRepo::open()?;
-    let args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-    let output = repo.log(&args_refs)?;
-    println!("{output}");
-    Ok(())
+    let mut args_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    let snapshot;
+    if let Some(rev) = main_rev {
+        snapshot = resolve_snapshot(&repo, rev)?;
+        args_refs.insert(0, &snapshot);
+    }
+
+    repo.log(&args_refs)
+}
+
+/// Resolve the side snapshot for `main_rev`, announcing which main commit it matched.
+///
+/// # Errors
+///
+/// Returns `NoSideSnapshot` if no side commit is linked to the revision or its ancestors.
+pub fn resolve_snapshot(repo: &SideRepo, main_rev: &str) -> Result<String> {
+    let (side_sha, main_sha) = repo
+        .snapshot_for_main(main_rev)?
+        .ok_or_else(|| Error::NoSideSnapshot(main_rev.to_string()))?;
+
+    eprintln!(
+        "{} side {} recorded at main {}",
+        "[git-side]".dimmed(),
+        side_sha[..7.min(side_sha.len())].cyan(),
+        main_sha[..7.min(main_sha.len())].cyan()
+    );
+    Ok(side_sha)
 }
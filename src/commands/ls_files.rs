@@ -9,9 +9,5 @@ use crate::side_repo::SideRepo;
 pub fn run(args: &[String]) -> Result<()> {
     let repo = SideRepo::open()?;
     let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
-    let output = repo.ls_files(&arg_refs)?;
-    if !output.is_empty() {
-        println!("{output}");
-    }
-    Ok(())
+    repo.ls_files(&arg_refs)
 }
@@ -1,13 +1,21 @@
 pub mod add;
+pub mod archive;
 pub mod auto;
+pub mod bundle;
+pub mod clone;
 pub mod commit;
+pub mod completions;
+pub mod exec;
 pub mod hook;
 pub mod info;
 pub mod init;
+pub mod install_docs;
 pub mod log;
 pub mod ls_files;
+pub mod prune;
 pub mod pull;
 pub mod push;
 pub mod remote;
 pub mod rm;
+pub mod show;
 pub mod status;
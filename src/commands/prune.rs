@@ -0,0 +1,65 @@
+use std::io::{self, BufRead, Write};
+
+use colored::Colorize;
+
+use crate::error::Result;
+use crate::side_repo::SideRepo;
+use crate::tracked::TrackedPaths;
+
+/// Stop tracking paths that no longer exist in the work tree.
+/// Asks for confirmation unless `yes` is set.
+///
+/// # Errors
+///
+/// Returns an error if the side repo cannot be opened or `.side-tracked` cannot be updated.
+pub fn run(yes: bool) -> Result<()> {
+    let repo = SideRepo::open()?;
+    if !repo.is_initialized() {
+        println!("{}", "Nothing to prune.".yellow());
+        return Ok(());
+    }
+
+    let mut tracked = TrackedPaths::load(&repo)?;
+    let missing = tracked.missing(&repo.work_tree);
+
+    if missing.is_empty() {
+        println!("{}", "Nothing to prune: all tracked paths exist.".green());
+        return Ok(());
+    }
+
+    println!("Tracked paths not found in the work tree:");
+    for path in &missing {
+        println!("    - {}", path.display());
+    }
+
+    if !yes && !confirm(&format!("Stop tracking {} path(s)?", missing.len())) {
+        println!("{}", "Aborted.".yellow());
+        return Ok(());
+    }
+
+    for path in &missing {
+        tracked.remove(path);
+        repo.unstage(path)?;
+    }
+    tracked.save()?;
+    repo.stage_tracked_file()?;
+
+    println!(
+        "{} {} path(s) pruned from .side-tracked",
+        "Done.".green().bold(),
+        missing.len().to_string().cyan()
+    );
+    Ok(())
+}
+
+/// Ask a yes/no question on the terminal; anything but "y"/"yes" (or EOF) is no.
+fn confirm(question: &str) -> bool {
+    print!("{question} [y/N] ");
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
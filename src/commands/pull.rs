@@ -1,24 +1,27 @@
 use colored::Colorize;
 
 use crate::error::Result;
+use crate::settings::Settings;
 use crate::side_repo::SideRepo;
 
-/// Pull side repo from remote.
+/// Pull side repo from remote (`from`, or `side.remote`).
 /// Uses fetch + reset to avoid conflicts — remote always wins.
 ///
 /// # Errors
 ///
 /// Returns an error if the side repo cannot be opened or pull fails.
-pub fn run() -> Result<()> {
+pub fn run(from: Option<&str>) -> Result<()> {
     let repo = SideRepo::open()?;
     repo.ensure_initialized()?;
 
-    // Fetch from origin
-    repo.git(&["fetch", "origin"])?;
+    let mut settings = Settings::load(&repo)?;
+    if let Some(remote) = from {
+        settings.remote = remote.to_string();
+    }
 
-    // Reset to origin/main (remote wins, no conflicts)
-    repo.git(&["reset", "--hard", "origin/main"])?;
+    // Fetch and reset to the remote side branch (remote wins, no conflicts)
+    repo.pull(&settings)?;
 
-    println!("{}", "Pulled from remote.".green().bold());
+    println!("{} {}", "Pulled from".green().bold(), settings.remote.cyan());
     Ok(())
 }
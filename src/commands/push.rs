@@ -1,21 +1,54 @@
 use colored::Colorize;
 
-use crate::error::Result;
+use crate::error::{Error, Result};
+use crate::settings::Settings;
 use crate::side_repo::SideRepo;
 
-/// Push side repo to remote.
-/// Uses force push — local always wins, no conflicts.
+/// Push side repo to every push target (`side.pushRemote`, or all remotes).
+/// Refuses to overwrite side commits pushed from elsewhere unless `force` is set.
 ///
 /// # Errors
 ///
-/// Returns an error if the side repo cannot be opened or push fails.
-pub fn run() -> Result<()> {
+/// Returns an error if the side repo cannot be opened, no remote is configured,
+/// or the push to any remote fails (including a diverged remote without `force`).
+pub fn run(force: bool) -> Result<()> {
     let repo = SideRepo::open()?;
     repo.ensure_initialized()?;
 
-    // Force push to origin main — local wins, no questions asked
-    repo.push()?;
+    let results = repo.push(&Settings::load(&repo)?, force);
+    if results.is_empty() {
+        return Err(Error::NoRemote);
+    }
 
-    println!("{}", "Pushed to remote.".green().bold());
-    Ok(())
+    let mut failed = Vec::new();
+    for (remote, result) in results {
+        match result {
+            Ok(kept) => {
+                println!("{} {}", "Pushed to".green().bold(), remote.cyan());
+                for refname in kept {
+                    println!(
+                        "    {} {refname} (the remote has newer commits; pull in that project)",
+                        "left as is:".yellow()
+                    );
+                }
+            }
+            Err(e) => {
+                eprintln!("{} {}", "Push failed:".red().bold(), remote.cyan());
+                let detail = match e {
+                    Error::GitCommandFailed(stderr) => stderr,
+                    e => e.to_string(),
+                };
+                for line in detail.lines().filter(|l| !l.trim().is_empty()) {
+                    eprintln!("    {line}");
+                }
+                failed.push(remote);
+            }
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(Error::PushFailed(failed.join(", ")))
+    }
 }
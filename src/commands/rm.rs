@@ -5,7 +5,7 @@ use colored::Colorize;
 use crate::error::{Error, Result};
 use crate::git;
 use crate::side_repo::SideRepo;
-use crate::tracked::TrackedPaths;
+use crate::tracked::{self, TrackedPaths};
 
 /// Remove a path from side tracking.
 ///
@@ -16,12 +16,7 @@ pub fn run(path: &Path) -> Result<()> {
     let work_tree = git::repo_root()?;
 
     // Normalize path: make it relative to work tree
-    let relative_path = if path.is_absolute() {
-        path.strip_prefix(&work_tree)
-            .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
-    } else {
-        path.to_path_buf()
-    };
+    let relative_path = tracked::normalize(&work_tree, path);
 
     // Open side repo
     let repo = SideRepo::open()?;
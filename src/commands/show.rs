@@ -0,0 +1,27 @@
+use crate::commands::log::resolve_snapshot;
+use crate::error::Result;
+use crate::side_repo::SideRepo;
+
+/// Show a side repo object (mirrors git show).
+/// With `main_rev`, shows the side snapshot closest to that main repo revision.
+///
+/// # Errors
+///
+/// Returns an error if the side repo cannot be opened, no snapshot matches, or git show fails.
+pub fn run(main_rev: Option<&str>, args: &[String]) -> Result<()> {
+    let repo = SideRepo::open()?;
+    let mut show_args: Vec<&str> = vec!["show"];
+
+    let snapshot;
+    if let Some(rev) = main_rev {
+        snapshot = resolve_snapshot(&repo, rev)?;
+        show_args.push(&snapshot);
+    }
+    show_args.extend(args.iter().map(String::as_str));
+
+    if !repo.is_initialized() {
+        println!("Side repo not initialized. No history yet.");
+        return Ok(());
+    }
+    repo.stream(&show_args)
+}
@@ -1,14 +1,132 @@
+use colored::Colorize;
+
 use crate::error::Result;
-use crate::side_repo::SideRepo;
+use crate::settings::Settings;
+use crate::side_repo::{PushStatus, SideRepo};
+use crate::tracked::TrackedPaths;
 
-/// Show side repo status.
+/// Show side repo status: per tracked path, what the next `auto` would record.
 ///
 /// # Errors
 ///
-/// Returns an error if the side repo cannot be opened or status command fails.
+/// Returns an error if the side repo cannot be opened or its state cannot be read.
 pub fn run() -> Result<()> {
     let repo = SideRepo::open()?;
-    let output = repo.status()?;
-    println!("{output}");
+
+    if !repo.is_initialized() {
+        println!("Side repo not initialized. Use 'git side add <path>' to start tracking files.");
+        return Ok(());
+    }
+
+    if repo.per_branch()? {
+        let active = repo
+            .current_branch()?
+            .unwrap_or_else(|| "(detached)".to_string());
+        let expected = repo.branch_for_main()?;
+        if active == expected {
+            println!("Side branch: {} (per-branch mode)", active.cyan());
+        } else {
+            println!(
+                "Side branch: {} (per-branch mode, main is on {}; next 'auto' switches)",
+                active.cyan(),
+                expected.yellow()
+            );
+        }
+    } else if let Some(branch) = repo.current_branch()? {
+        println!("On side branch {}", branch.cyan());
+    }
+
+    let tracked = TrackedPaths::load(&repo)?;
+    if tracked.is_empty() {
+        println!("No tracked paths. Use 'git side add <path>' to start tracking files.");
+        return Ok(());
+    }
+
+    let max_file_size = Settings::load(&repo)?.max_file_size;
+    let statuses = tracked.status(&repo, max_file_size)?;
+    let pending = statuses.iter().filter(|s| s.has_changes()).count();
+
+    println!();
+    println!("Tracked paths:");
+    for status in &statuses {
+        let state = if !status.exists {
+            "missing".red().bold()
+        } else if !status.has_changes() {
+            "clean".dimmed()
+        } else {
+            "changed".yellow()
+        };
+        println!("  {} ({state})", status.path.display());
+
+        if !status.exists {
+            println!("      {}  {}", "missing: ".red().bold(), status.path.display());
+        }
+        for (kind, path) in &status.staged {
+            let label = match kind {
+                'A' => "new:     ".green(),
+                'D' => "deleted: ".red(),
+                _ => "modified:".yellow(),
+            };
+            println!("      {label}  {path} {}", "(staged)".dimmed());
+        }
+        for path in &status.new {
+            println!("      {}  {path}", "new:     ".green());
+        }
+        for path in &status.modified {
+            println!("      {}  {path}", "modified:".yellow());
+        }
+        for path in &status.deleted {
+            println!("      {}  {path}", "deleted: ".red());
+        }
+        for (path, size) in &status.too_large {
+            println!(
+                "      {}  {path} {}",
+                "skipped: ".dimmed(),
+                format!("({size} bytes, over side.maxFileSize)").dimmed()
+            );
+        }
+    }
+
+    let missing = statuses.iter().filter(|s| !s.exists).count();
+    if missing > 0 {
+        println!();
+        println!(
+            "{} tracked path(s) no longer exist; 'git side prune' stops tracking them.",
+            missing.to_string().red()
+        );
+    }
+
+    println!();
+    if pending == 0 {
+        println!("Nothing to sync, side repo is up to date.");
+    } else {
+        println!(
+            "{} tracked path(s) with changes; 'git side auto' or 'git side commit -a' will record them.",
+            pending.to_string().cyan()
+        );
+    }
+
+    let pushes = repo.last_pushes();
+    if !pushes.is_empty() {
+        println!();
+        print_last_pushes(&pushes, "");
+    }
+
     Ok(())
 }
+
+/// Print the outcome of the last push to each remote, each line prefixed with `indent`.
+pub fn print_last_pushes(pushes: &[PushStatus], indent: &str) {
+    println!("{indent}Last push:");
+    for push in pushes {
+        match &push.error {
+            None => println!("{indent}  {} {} {}", push.remote.cyan(), push.at, "ok".green()),
+            Some(error) => println!(
+                "{indent}  {} {} {} {error}",
+                push.remote.cyan(),
+                push.at,
+                "failed:".red().bold()
+            ),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::BTreeSet;
+use std::ffi::OsStr;
+
+use clap_complete::engine::{CompletionCandidate, PathCompleter, ValueCompleter};
+
+use crate::git;
+use crate::side_repo::SideRepo;
+use crate::tracked::TrackedPaths;
+
+/// Environment variable that switches git-side into completion mode.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Paths listed in `.side-tracked`, for `rm`, `commit` and `add -p`.
+#[must_use]
+pub fn tracked_paths(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    let Ok(repo) = SideRepo::open() else {
+        return Vec::new();
+    };
+    let Ok(tracked) = TrackedPaths::load(&repo) else {
+        return Vec::new();
+    };
+
+    tracked
+        .paths()
+        .iter()
+        .map(|p| p.to_string_lossy().into_owned())
+        .filter(|p| p.starts_with(current.as_ref()))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Candidates for `add`: ignored files first, then other untracked files,
+/// falling back to plain path completion when neither matches.
+#[must_use]
+pub fn addable_paths(current: &OsStr) -> Vec<CompletionCandidate> {
+    let prefix = current.to_string_lossy();
+    let tracked: BTreeSet<String> = SideRepo::open()
+        .ok()
+        .and_then(|repo| TrackedPaths::load(&repo).ok())
+        .map(|t| t.paths().iter().map(|p| p.to_string_lossy().into_owned()).collect())
+        .unwrap_or_default();
+
+    let list = |extra: &[&str]| -> Vec<String> {
+        let mut args = vec!["ls-files", "--others", "--exclude-standard", "--directory"];
+        args.extend_from_slice(extra);
+        git::run(&args)
+            .map(|out| out.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    };
+
+    let ignored = list(&["--ignored"]);
+    let untracked = list(&[]);
+
+    let candidates: Vec<CompletionCandidate> = ignored
+        .iter()
+        .map(|p| (p, "ignored"))
+        .chain(untracked.iter().map(|p| (p, "untracked")))
+        .filter(|(p, _)| p.starts_with(prefix.as_ref()))
+        .filter(|(p, _)| !tracked.contains(p.trim_end_matches('/')))
+        .enumerate()
+        .map(|(i, (p, tag))| {
+            CompletionCandidate::new(p)
+                .help(Some(tag.into()))
+                .display_order(Some(i))
+        })
+        .collect();
+
+    if candidates.is_empty() {
+        PathCompleter::any().complete(current)
+    } else {
+        candidates
+    }
+}
@@ -1,29 +1,123 @@
 use std::collections::HashMap;
+use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::error::{Error, Result};
+use crate::git;
 
-/// Get the config directory path (platform-specific via `dirs::config_dir()`).
-fn config_dir() -> PathBuf {
-    dirs::config_dir()
-        .unwrap_or_else(|| PathBuf::from("~/.config"))
-        .join("git-side")
+/// Overrides both the config and the data directory.
+const HOME_VAR: &str = "GIT_SIDE_HOME";
+
+/// Overrides the config directory (cache, paths and central storage files).
+const CONFIG_DIR_VAR: &str = "GIT_SIDE_CONFIG_DIR";
+
+/// Overrides the default base path for side repos.
+const DATA_DIR_VAR: &str = "GIT_SIDE_DATA_DIR";
+
+/// Git config key (main repo, global or system) holding the base path for side repos.
+const BASE_PATH_KEY: &str = "side.basePath";
+
+/// Read a directory from an environment variable, ignoring unset or empty values.
+///
+/// Relative XDG values are ignored (as the XDG spec requires); a relative `GIT_SIDE_*`
+/// value is an error, since it would resolve differently in every directory.
+fn env_dir(var: &str) -> Result<Option<PathBuf>> {
+    let Some(dir) = env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+    else {
+        return Ok(None);
+    };
+    if dir.is_absolute() {
+        Ok(Some(dir))
+    } else if var.starts_with("XDG_") {
+        Ok(None)
+    } else {
+        Err(Error::InvalidSetting {
+            key: var.to_string(),
+            reason: format!("'{}' is not an absolute path", dir.display()),
+        })
+    }
+}
+
+/// Resolve a git-side directory: explicit override, `GIT_SIDE_HOME`, the XDG variable,
+/// the platform directory, then `$HOME/<home_relative>`.
+fn resolve_dir(
+    override_var: &str,
+    xdg_var: &str,
+    platform: Option<PathBuf>,
+    home_relative: &str,
+) -> Result<PathBuf> {
+    if let Some(dir) = env_dir(override_var)? {
+        return Ok(dir);
+    }
+    if let Some(dir) = env_dir(HOME_VAR)? {
+        return Ok(dir);
+    }
+    env_dir(xdg_var)?
+        .or(platform)
+        .or_else(|| dirs::home_dir().map(|home| home.join(home_relative)))
+        .map(|dir| dir.join("git-side"))
+        .ok_or(Error::NoHomeDir)
+}
+
+/// Whether `override_var` or `GIT_SIDE_HOME` points git-side somewhere on purpose.
+fn overridden(override_var: &str) -> bool {
+    [override_var, HOME_VAR]
+        .iter()
+        .any(|var| env::var_os(var).is_some_and(|v| !v.is_empty()))
+}
+
+/// Where an older git-side (or one without overrides) keeps its files: the
+/// platform directory. `None` when that is where git-side looks now anyway.
+fn platform_dir(platform: Option<PathBuf>, current: &Path) -> Option<PathBuf> {
+    platform
+        .map(|dir| dir.join("git-side"))
+        .filter(|dir| dir != current)
+}
+
+/// Get the config directory path.
+///
+/// `GIT_SIDE_CONFIG_DIR`, then `GIT_SIDE_HOME`, then `$XDG_CONFIG_HOME/git-side`,
+/// then the platform default (via `dirs::config_dir()`).
+///
+/// Refuses to start over in a new location while the platform directory still holds
+/// the registry, which would lose track of custom paths and centrally stored projects.
+/// An explicit `GIT_SIDE_*` location is a deliberate fresh start and is not checked.
+fn config_dir() -> Result<PathBuf> {
+    let dir = resolve_dir(CONFIG_DIR_VAR, "XDG_CONFIG_HOME", dirs::config_dir(), ".config")?;
+    if !overridden(CONFIG_DIR_VAR)
+        && let Some(old) = platform_dir(dirs::config_dir(), &dir)
+        && old.is_dir()
+        && !dir.exists()
+    {
+        return Err(Error::ConfigStranded {
+            found: old,
+            expected: dir,
+        });
+    }
+    Ok(dir)
 }
 
 /// Get the cache file path.
-fn cache_file() -> PathBuf {
-    config_dir().join("cache")
+fn cache_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("cache"))
 }
 
 /// Get the paths file path.
-fn paths_file() -> PathBuf {
-    config_dir().join("paths")
+fn paths_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("paths"))
+}
+
+/// Get the central storage file path.
+fn central_file() -> Result<PathBuf> {
+    Ok(config_dir()?.join("central"))
 }
 
 /// Ensure the config directory exists.
 fn ensure_config_dir() -> Result<()> {
-    let dir = config_dir();
+    let dir = config_dir()?;
     if !dir.exists() {
         fs::create_dir_all(&dir).map_err(|e| Error::CreateDir {
             path: dir,
@@ -90,7 +184,7 @@ pub fn hash_path(path: &Path) -> String {
 ///
 /// Returns an error if the cache file cannot be read.
 pub fn cache_lookup(path_hash: &str) -> Result<Option<String>> {
-    let map = read_kv_file(&cache_file())?;
+    let map = read_kv_file(&cache_file()?)?;
     Ok(map.get(path_hash).cloned())
 }
 
@@ -100,9 +194,9 @@ pub fn cache_lookup(path_hash: &str) -> Result<Option<String>> {
 ///
 /// Returns an error if the cache file cannot be written.
 pub fn cache_store(path_hash: &str, root_sha: &str) -> Result<()> {
-    let mut map = read_kv_file(&cache_file())?;
+    let mut map = read_kv_file(&cache_file()?)?;
     map.insert(path_hash.to_string(), root_sha.to_string());
-    write_kv_file(&cache_file(), &map)
+    write_kv_file(&cache_file()?, &map)
 }
 
 /// Paths: lookup custom base path by root SHA.
@@ -111,7 +205,7 @@ pub fn cache_store(path_hash: &str, root_sha: &str) -> Result<()> {
 ///
 /// Returns an error if the paths file cannot be read.
 pub fn paths_lookup(root_sha: &str) -> Result<Option<PathBuf>> {
-    let map = read_kv_file(&paths_file())?;
+    let map = read_kv_file(&paths_file()?)?;
     Ok(map.get(root_sha).map(PathBuf::from))
 }
 
@@ -121,18 +215,110 @@ pub fn paths_lookup(root_sha: &str) -> Result<Option<PathBuf>> {
 ///
 /// Returns an error if the paths file cannot be written.
 pub fn paths_store(root_sha: &str, base_path: &Path) -> Result<()> {
-    let mut map = read_kv_file(&paths_file())?;
+    let mut map = read_kv_file(&paths_file()?)?;
     map.insert(
         root_sha.to_string(),
         base_path.to_string_lossy().to_string(),
     );
-    write_kv_file(&paths_file(), &map)
+    write_kv_file(&paths_file()?, &map)
 }
 
-/// Get the default base path for side repos.
+/// Central: lookup the shared side repo holding a project, by root SHA.
+///
+/// # Errors
+///
+/// Returns an error if the central storage file cannot be read.
+pub fn central_lookup(root_sha: &str) -> Result<Option<PathBuf>> {
+    let map = read_kv_file(&central_file()?)?;
+    Ok(map.get(root_sha).map(PathBuf::from))
+}
+
+/// Central: record that a project is stored in the shared side repo at `repo`.
+///
+/// # Errors
+///
+/// Returns an error if the central storage file cannot be written.
+pub fn central_store(root_sha: &str, repo: &Path) -> Result<()> {
+    let mut map = read_kv_file(&central_file()?)?;
+    map.insert(root_sha.to_string(), repo.to_string_lossy().to_string());
+    write_kv_file(&central_file()?, &map)
+}
+
+/// Get the default location of the shared side repo used by central storage.
+///
+/// # Errors
+///
+/// Returns an error if the base path cannot be determined.
+pub fn default_central_repo() -> Result<PathBuf> {
+    Ok(configured_base_path()?.join("central.git"))
+}
+
+/// Get the base path for a project's side repo: its `init --path` mapping,
+/// then `side.basePath` from git config, then the default.
+///
+/// # Errors
+///
+/// Returns an error if the paths file cannot be read, the base path cannot be
+/// determined, or the project's side repo was left in the platform directory
+/// while side repos are now looked up elsewhere.
+pub fn base_path(root_sha: &str) -> Result<PathBuf> {
+    if let Some(path) = paths_lookup(root_sha)? {
+        return Ok(path);
+    }
+    let base = configured_base_path()?;
+    if let Some(found) = stranded_repo(root_sha, &base) {
+        return Err(Error::SideRepoStranded {
+            found,
+            expected: base.join(root_sha),
+        });
+    }
+    Ok(base)
+}
+
+/// A side repo for `root_sha` in the platform data directory while `base` points
+/// elsewhere (set through `XDG_DATA_HOME` or `side.basePath`) and has none.
+///
+/// A base set explicitly through `GIT_SIDE_*` is a deliberate fresh start, so never.
 #[must_use]
-pub fn default_base_path() -> PathBuf {
-    dirs::data_local_dir()
-        .unwrap_or_else(|| PathBuf::from("~/.local/share"))
-        .join("git-side")
+pub fn stranded_repo(root_sha: &str, base: &Path) -> Option<PathBuf> {
+    if overridden(DATA_DIR_VAR) && default_base_path().is_ok_and(|dir| dir == base) {
+        return None;
+    }
+    let found = platform_dir(dirs::data_local_dir(), base)?.join(root_sha);
+    (found.join("HEAD").exists() && !base.join(root_sha).exists()).then_some(found)
+}
+
+/// Get `side.basePath` from git config (`~` expanded), or the default base path.
+///
+/// # Errors
+///
+/// Returns an error if `side.basePath` is relative, or no default can be determined.
+pub fn configured_base_path() -> Result<PathBuf> {
+    let Some(path) = git::run(&["config", "--type=path", "--get", BASE_PATH_KEY])
+        .ok()
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+    else {
+        return default_base_path();
+    };
+    if path.is_absolute() {
+        Ok(path)
+    } else {
+        Err(Error::InvalidSetting {
+            key: BASE_PATH_KEY.to_string(),
+            reason: format!("'{}' is not an absolute path", path.display()),
+        })
+    }
+}
+
+/// Get the default base path for side repos.
+///
+/// `GIT_SIDE_DATA_DIR`, then `GIT_SIDE_HOME`, then `$XDG_DATA_HOME/git-side`,
+/// then the platform default (via `dirs::data_local_dir()`).
+///
+/// # Errors
+///
+/// Returns an error if an override is relative or no home directory can be found.
+pub fn default_base_path() -> Result<PathBuf> {
+    resolve_dir(DATA_DIR_VAR, "XDG_DATA_HOME", dirs::data_local_dir(), ".local/share")
 }
@@ -11,6 +11,9 @@ pub enum Error {
     #[error("git command failed: {0}")]
     GitCommandFailed(String),
 
+    #[error("git exited with status {0}")]
+    GitExited(i32),
+
     #[error("path not found: {}", .0.display())]
     PathNotFound(PathBuf),
 
@@ -20,9 +23,89 @@ pub enum Error {
     #[error("path not tracked: {}", .0.display())]
     PathNotTracked(PathBuf),
 
+    #[error(
+        "push to {remote} rejected: the side remote has commits you don't have \
+         (run 'git side pull' to take them, or 'git side push --force' to overwrite them)"
+    )]
+    PushRejected { remote: String },
+
+    #[error("no side remote configured (add one with 'git side remote add <name> <url>')")]
+    NoRemote,
+
+    #[error("side remote {remote} has no branch {branch} to pull")]
+    NoRemoteBranch { remote: String, branch: String },
+
+    #[error(
+        "side branch {branch} and {remote}/{branch} have both moved on; not pulling \
+         (run 'git side pull' to take the remote state, or 'git side push --force' to keep yours)"
+    )]
+    SideDiverged { remote: String, branch: String },
+
+    #[error("side files have changes that are not committed yet; not pulling (run 'git side auto' first)")]
+    SideChangesPending,
+
+    #[error("push failed for: {0}")]
+    PushFailed(String),
+
+    #[error("message template '{0}' rendered an empty commit message")]
+    EmptyMessage(String),
+
     #[error("nothing to commit")]
     NothingToCommit,
 
+    #[error("no side snapshot found at or before main revision {0}")]
+    NoSideSnapshot(String),
+
+    #[error(
+        "side repo for this project is at {}, but side repos are now looked up in {} \
+         (XDG_DATA_HOME or side.basePath changed); run 'git side init --move' to move it",
+        found.display(),
+        expected.display()
+    )]
+    SideRepoStranded { found: PathBuf, expected: PathBuf },
+
+    #[error(
+        "git-side config is in {}, but is now looked up in {} (XDG_CONFIG_HOME changed); \
+         move the directory there, or unset the variable",
+        found.display(),
+        expected.display()
+    )]
+    ConfigStranded { found: PathBuf, expected: PathBuf },
+
+    #[error("cannot determine the home directory (set GIT_SIDE_HOME)")]
+    NoHomeDir,
+
+    #[error("side repo already exists at {}", .0.display())]
+    SideRepoExists(PathBuf),
+
+    #[error(
+        "side repo at {} belongs to project {found}, not {expected} (was it copied or was the paths config edited?)",
+        git_dir.display()
+    )]
+    ProjectMismatch {
+        git_dir: PathBuf,
+        expected: String,
+        found: String,
+    },
+
+    #[error("remote {url} does not look like this project's side repo: {reason}")]
+    UnrelatedRemote { url: String, reason: String },
+
+    #[error("bundle {} is not for this project: {reason}", path.display())]
+    UnrelatedBundle { path: PathBuf, reason: String },
+
+    #[error(
+        "bundle would drop side commits on {0} that it does not contain \
+         (use 'git side bundle apply --force' to replace them)"
+    )]
+    BundleDiverged(String),
+
+    #[error("invalid value for {key}: {reason}")]
+    InvalidSetting { key: String, reason: String },
+
+    #[error("{} is {size} bytes, over the side.maxFileSize limit of {limit} bytes", path.display())]
+    FileTooLarge { path: PathBuf, size: u64, limit: u64 },
+
     #[error("no tracked paths configured")]
     NoTrackedPaths,
 
@@ -32,6 +115,9 @@ pub enum Error {
     #[error("hook not installed: {0}")]
     HookNotInstalled(String),
 
+    #[error("hook {hook} was written but Git will not run it: {reason}")]
+    HookInactive { hook: String, reason: String },
+
     #[error("failed to read {}: {source}", path.display())]
     ReadFile {
         path: PathBuf,
@@ -53,6 +139,23 @@ pub enum Error {
         source: std::io::Error,
     },
 
+    #[error("completions are not supported for shell {0}")]
+    UnsupportedShell(String),
+
+    #[error("failed to write completions: {0}")]
+    WriteCompletions(#[source] std::io::Error),
+
+    #[error("not supported for projects in central storage: {0}")]
+    CentralUnsupported(&'static str),
+
+    #[error("failed to move side repo from {} to {}: {source}", from.display(), to.display())]
+    MoveRepo {
+        from: PathBuf,
+        to: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
     #[error("invalid config format in {}", path.display())]
     InvalidConfig { path: PathBuf },
 }
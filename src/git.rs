@@ -1,16 +1,26 @@
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::error::{Error, Result};
 
+/// Whether streamed git commands may start a pager.
+static PAGER_ENABLED: AtomicBool = AtomicBool::new(true);
+
 /// Run a git command and return stdout on success.
 ///
 /// # Errors
 ///
 /// Returns an error if the git command fails to execute or exits with non-zero status.
 pub fn run(args: &[&str]) -> Result<String> {
-    let output = Command::new("git")
-        .args(args)
+    let mut command = Command::new("git");
+    command.args(args);
+    capture(&mut command)
+}
+
+/// Run a prepared git command and return stdout on success.
+fn capture(command: &mut Command) -> Result<String> {
+    let output = command
         .output()
         .map_err(|e| Error::GitCommandFailed(format!("failed to execute git: {e}")))?;
 
@@ -22,29 +32,88 @@ pub fn run(args: &[&str]) -> Result<String> {
     }
 }
 
+/// Build a git command targeting a specific work-tree and git-dir.
+fn command_with_paths(git_dir: &Path, work_tree: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new("git");
+    command
+        .current_dir(work_tree)
+        .env("GIT_DIR", git_dir)
+        .env("GIT_WORK_TREE", work_tree)
+        // Clear any inherited git environment from hooks
+        .env_remove("GIT_INDEX_FILE")
+        .env_remove("GIT_OBJECT_DIRECTORY")
+        .env_remove("GIT_ALTERNATE_OBJECT_DIRECTORIES")
+        .args(args);
+    command
+}
+
 /// Run a git command with a specific work-tree and git-dir.
 ///
 /// # Errors
 ///
 /// Returns an error if the git command fails to execute or exits with non-zero status.
 pub fn run_with_paths(git_dir: &Path, work_tree: &Path, args: &[&str]) -> Result<String> {
-    let output = Command::new("git")
-        .current_dir(work_tree)
+    capture(&mut command_with_paths(git_dir, work_tree, args))
+}
+
+/// Run a git command with a specific work-tree and git-dir, with git's messages
+/// left untranslated so callers can match on its stderr.
+///
+/// # Errors
+///
+/// Returns an error if the git command fails to execute or exits with non-zero status.
+pub fn run_with_paths_untranslated(git_dir: &Path, work_tree: &Path, args: &[&str]) -> Result<String> {
+    capture(command_with_paths(git_dir, work_tree, args).env("LC_ALL", "C"))
+}
+
+/// Run a git command against a bare repository (which need not exist yet for `init`).
+/// Any git environment inherited from hooks is cleared so it cannot redirect the command.
+///
+/// # Errors
+///
+/// Returns an error if the git command fails to execute or exits with non-zero status.
+pub fn run_bare(git_dir: &Path, args: &[&str]) -> Result<String> {
+    let mut command = Command::new("git");
+    command
         .env("GIT_DIR", git_dir)
-        .env("GIT_WORK_TREE", work_tree)
-        // Clear any inherited git environment from hooks
+        .env_remove("GIT_WORK_TREE")
+        .env_remove("GIT_COMMON_DIR")
         .env_remove("GIT_INDEX_FILE")
         .env_remove("GIT_OBJECT_DIRECTORY")
         .env_remove("GIT_ALTERNATE_OBJECT_DIRECTORIES")
-        .args(args)
-        .output()
+        .args(args);
+    capture(&mut command)
+}
+
+/// Disable git's pager for commands run attached to the terminal (`--no-pager`).
+pub fn disable_pager() {
+    PAGER_ENABLED.store(false, Ordering::Relaxed);
+}
+
+/// Run a git command with a specific work-tree and git-dir, attached to the terminal.
+///
+/// Stdin, stdout and stderr are inherited, so editors, prompts, the pager
+/// (`core.pager`) and colors (`color.ui`) work as for a native git command.
+///
+/// # Errors
+///
+/// Returns an error if the git command fails to execute or exits with non-zero status.
+pub fn run_with_paths_interactive(git_dir: &Path, work_tree: &Path, args: &[&str]) -> Result<()> {
+    let mut full_args = Vec::with_capacity(args.len() + 1);
+    if !PAGER_ENABLED.load(Ordering::Relaxed) {
+        full_args.push("--no-pager");
+    }
+    full_args.extend_from_slice(args);
+
+    let status = command_with_paths(git_dir, work_tree, &full_args)
+        .status()
         .map_err(|e| Error::GitCommandFailed(format!("failed to execute git: {e}")))?;
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    if status.success() {
+        Ok(())
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
-        Err(Error::GitCommandFailed(stderr))
+        // Git already reported the problem on the inherited stderr
+        Err(Error::GitExited(status.code().unwrap_or(1)))
     }
 }
 
@@ -81,6 +150,34 @@ pub fn git_dir() -> Result<PathBuf> {
     Ok(PathBuf::from(dir))
 }
 
+/// Get the hooks directory Git will actually use for the current repository.
+/// Honours `core.hooksPath`, so hook managers like husky or lefthook are accounted for.
+///
+/// # Errors
+///
+/// Returns an error if not inside a git repository.
+pub fn hooks_dir() -> Result<PathBuf> {
+    hook_path("")
+}
+
+/// Get the absolute path Git will execute for a given hook name.
+///
+/// # Errors
+///
+/// Returns an error if not inside a git repository.
+pub fn hook_path(hook_name: &str) -> Result<PathBuf> {
+    if !is_in_repo() {
+        return Err(Error::NotInGitRepo);
+    }
+    let git_path = if hook_name.is_empty() {
+        "hooks".to_string()
+    } else {
+        format!("hooks/{hook_name}")
+    };
+    let path = run(&["rev-parse", "--path-format=absolute", "--git-path", &git_path])?;
+    Ok(PathBuf::from(path))
+}
+
 /// Get the initial commit SHA of the repository (project identifier).
 ///
 /// # Errors
@@ -97,6 +194,45 @@ pub fn initial_commit_sha() -> Result<String> {
     Ok(sha.lines().next().unwrap_or(&sha).to_string())
 }
 
+/// Get the current branch name of the main repository.
+/// Returns `None` on a detached HEAD.
+///
+/// # Errors
+///
+/// Returns an error if not inside a git repository.
+pub fn current_branch() -> Result<Option<String>> {
+    if !is_in_repo() {
+        return Err(Error::NotInGitRepo);
+    }
+    Ok(run(&["symbolic-ref", "--short", "-q", "HEAD"])
+        .ok()
+        .filter(|b| !b.is_empty()))
+}
+
+/// Resolve a revision in the main repository to a full commit SHA.
+///
+/// # Errors
+///
+/// Returns an error if the revision does not name a commit.
+pub fn resolve_commit(rev: &str) -> Result<String> {
+    let spec = format!("{rev}^{{commit}}");
+    run(&["rev-parse", "--verify", "--quiet", &spec])
+        .map_err(|_| Error::GitCommandFailed(format!("unknown main revision: {rev}")))
+}
+
+/// Check whether `sha` names a commit in the main repository.
+#[must_use]
+pub fn has_commit(sha: &str) -> bool {
+    let spec = format!("{sha}^{{commit}}");
+    run(&["cat-file", "-e", &spec]).is_ok()
+}
+
+/// Get the SHA of the main repository's `HEAD`, if there is one.
+#[must_use]
+pub fn head_sha() -> Option<String> {
+    run(&["rev-parse", "--verify", "--quiet", "HEAD"]).ok()
+}
+
 /// Get the last commit message from the main repository.
 ///
 /// # Errors
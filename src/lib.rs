@@ -1,6 +1,11 @@
+pub mod background;
 pub mod commands;
+pub mod complete;
 pub mod config;
 pub mod error;
 pub mod git;
+pub mod message;
+pub mod settings;
 pub mod side_repo;
+pub mod timestamp;
 pub mod tracked;
@@ -1,10 +1,18 @@
 use std::path::PathBuf;
 use std::process::ExitCode;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::{ArgValueCompleter, CompleteEnv, Shell};
 use colored::Colorize;
 
 use git_side::commands;
+use git_side::commands::archive::{ArchiveFormat, ArchiveOptions};
+use git_side::commands::auto::{AutoOptions, RunMode};
+use git_side::commands::commit::MessageSource;
+use git_side::commands::hook::HookKind;
+use git_side::complete;
+use git_side::error::{Error, Result};
+use git_side::git;
 
 #[derive(Parser)]
 #[command(
@@ -14,6 +22,10 @@ use git_side::commands;
     propagate_version = true
 )]
 struct Cli {
+    /// Do not pipe side repo output into a pager
+    #[arg(long, global = true)]
+    no_pager: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -21,34 +33,80 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Track a file or directory (forced, bypasses gitignore)
+    #[command(visible_alias = "stage")]
     Add {
-        /// Path to track
-        path: PathBuf,
+        /// Interactively stage hunks of tracked files instead of tracking a new path
+        #[arg(short, long)]
+        patch: bool,
+
+        /// Path to track (with --patch: limit to this tracked path)
+        #[arg(required_unless_present = "patch", add = ArgValueCompleter::new(complete::addable_paths))]
+        path: Option<PathBuf>,
     },
 
     /// Untrack a path from side repo
     Rm {
         /// Path to untrack
+        #[arg(add = ArgValueCompleter::new(complete::tracked_paths))]
         path: PathBuf,
     },
 
+    /// Stop tracking paths that no longer exist
+    Prune {
+        /// Do not ask for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Show side repo status
     Status,
 
-    /// Commit staged changes to side repo
+    /// Commit changes to side repo (opens the editor if no message is given)
     Commit {
         /// Commit message
-        #[arg(short, long)]
-        message: String,
+        #[arg(short, long, conflicts_with = "template")]
+        message: Option<String>,
+
+        /// Stage modified, deleted and new files in all tracked paths first
+        #[arg(short, long, conflicts_with = "paths")]
+        all: bool,
+
+        /// Replace the last side commit
+        #[arg(long)]
+        amend: bool,
+
+        /// Build the message from a template (default: the project's configured template).
+        /// Placeholders: {message} {subject} {sha} {branch} {files} {count} {timestamp} {summary}
+        #[arg(long, value_name = "TEMPLATE", num_args = 0..=1, require_equals = true, default_missing_value = "")]
+        template: Option<String>,
+
+        /// Only commit these tracked paths
+        #[arg(add = ArgValueCompleter::new(complete::tracked_paths))]
+        paths: Vec<PathBuf>,
     },
 
     /// Show side repo history
     Log {
+        /// Start from the side snapshot closest to this main repo revision
+        #[arg(long, value_name = "REV")]
+        main: Option<String>,
+
         /// Additional arguments to pass to git log
         #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
         args: Vec<String>,
     },
 
+    /// Show a side repo commit (mirrors git show)
+    Show {
+        /// Show the side snapshot closest to this main repo revision (commit, tag, branch)
+        #[arg(long, value_name = "MAIN_REV")]
+        at: Option<String>,
+
+        /// Additional arguments to pass to git show
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+
     /// List files tracked in the side repo (mirrors git ls-files)
     LsFiles {
         /// Additional arguments to pass to git ls-files
@@ -57,13 +115,50 @@ enum Commands {
     },
 
     /// Sync side-tracked paths and commit using last main repo message
-    Auto,
+    Auto {
+        /// Run detached in the background, logging to the side repo (see 'hook log')
+        #[arg(long)]
+        background: bool,
+
+        /// Only print errors
+        #[arg(short, long)]
+        quiet: bool,
+
+        /// Commit only, do not push (see also the side.autoPush setting)
+        #[arg(long)]
+        no_push: bool,
+
+        /// Internal: marks the detached background process
+        #[arg(long, hide = true)]
+        detached: bool,
+    },
 
     /// Initialize side repo with optional custom path
     Init {
         /// Custom base path for side repo storage
-        #[arg(long)]
+        #[arg(long, conflicts_with = "central")]
         path: Option<PathBuf>,
+
+        /// Store this project in the shared multi-project side repo
+        #[arg(long)]
+        central: bool,
+
+        /// Move a side repo left in the platform data directory to the configured base path
+        #[arg(long = "move", conflicts_with_all = ["path", "central"])]
+        relocate: bool,
+
+        /// Commit to a side branch named after the current main branch
+        #[arg(long, overrides_with = "no_per_branch")]
+        per_branch: bool,
+
+        /// Always commit to the side repo's main branch (default)
+        #[arg(long, overrides_with = "per_branch")]
+        no_per_branch: bool,
+
+        /// Auto-commit message template (empty string resets to "{message}").
+        /// Placeholders: {message} {subject} {sha} {branch} {files} {count} {timestamp} {summary}
+        #[arg(long, value_name = "TEMPLATE")]
+        message_template: Option<String>,
     },
 
     /// Manage git hooks for auto-sync
@@ -75,6 +170,20 @@ enum Commands {
     /// Show info about git-side and current project
     Info,
 
+    /// Install man pages so "git help side" works (default: ~/.local/share/man/man1)
+    InstallDocs {
+        /// Directory to write the man pages to
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+
+    /// Print the shell completion script (e.g. "source <(git side completions bash)")
+    Completions {
+        /// Shell to generate completions for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+
     /// Manage side repo remotes (add, remove, list)
     Remote {
         /// Arguments to pass to git remote (e.g., "add origin <url>")
@@ -82,11 +191,89 @@ enum Commands {
         args: Vec<String>,
     },
 
-    /// Push side repo to remote (force push, local wins)
-    Push,
+    /// Run any git command against the side repo (e.g. "git side git rebase -i")
+    #[command(visible_alias = "exec")]
+    Git {
+        /// Arguments to pass to git
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true, required = true)]
+        args: Vec<String>,
+    },
+
+    /// Set up this project's side repo from an existing side remote
+    Clone {
+        /// URL of the side remote
+        url: String,
+
+        /// Skip checking that the remote's history belongs to this project
+        #[arg(long)]
+        no_verify: bool,
+    },
+
+    /// Push side repo to its remotes (refuses to overwrite commits pushed from elsewhere)
+    Push {
+        /// Overwrite the remote side branch, even if it has commits you don't have
+        #[arg(short, long)]
+        force: bool,
+    },
+
+    /// Pull side repo from remote (remote wins)
+    Pull {
+        /// Remote to pull from (default: side.remote, or origin)
+        #[arg(long, value_name = "REMOTE")]
+        from: Option<String>,
+    },
+
+    /// Move side history through a file instead of a remote (create, apply)
+    Bundle {
+        #[command(subcommand)]
+        action: BundleAction,
+    },
+
+    /// Write the side-tracked files at a revision to a tar or zip file
+    Archive {
+        /// Side revision to archive
+        #[arg(long, default_value = "HEAD")]
+        rev: String,
+
+        /// Archive the side snapshot closest to this main repo revision instead
+        #[arg(long, value_name = "MAIN_REV", conflicts_with = "rev")]
+        at: Option<String>,
+
+        /// Archive format (default: from the output file name, or tar)
+        #[arg(long, value_enum)]
+        format: Option<ArchiveFormat>,
+
+        /// Include the .side-tracked manifest
+        #[arg(long)]
+        manifest: bool,
+
+        /// File to write
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum BundleAction {
+    /// Write side commits made since the last bundle to a file
+    Create {
+        /// Bundle file to write
+        file: PathBuf,
+
+        /// Include the whole side history, not just what is new since the last bundle
+        #[arg(long)]
+        full: bool,
+    },
+
+    /// Bring the side branches in a bundle file into this project
+    Apply {
+        /// Bundle file to read
+        file: PathBuf,
 
-    /// Pull side repo from remote
-    Pull,
+        /// Replace side branches that have commits the bundle does not contain
+        #[arg(short, long)]
+        force: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -94,45 +281,167 @@ enum HookAction {
     /// Install git hook to run auto on commits
     Install {
         /// Hook to install (default: post-commit)
-        #[arg(long, default_value = "post-commit")]
-        on: String,
+        #[arg(long, value_enum, default_value_t = HookKind::PostCommit)]
+        on: HookKind,
     },
 
     /// Remove git hook
     Uninstall {
         /// Hook to remove (default: post-commit)
-        #[arg(long, default_value = "post-commit")]
-        on: String,
+        #[arg(long, value_enum, default_value_t = HookKind::PostCommit)]
+        on: HookKind,
+    },
+
+    /// List supported hooks and their install status
+    List,
+
+    /// Show recent hook and background runs
+    Log {
+        /// Number of runs to show
+        #[arg(short = 'n', long, default_value_t = 10)]
+        count: usize,
+
+        /// Only show failed runs
+        #[arg(long)]
+        failures: bool,
+    },
+
+    /// Run the action for a hook (called from installed hook scripts)
+    #[command(hide = true)]
+    Run {
+        /// Hook being run
+        #[arg(value_enum)]
+        kind: HookKind,
+
+        /// Internal: marks the detached background process
+        #[arg(long)]
+        detached: bool,
+
+        /// Arguments Git passed to the hook
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
     },
 }
 
 fn main() -> ExitCode {
+    CompleteEnv::with_factory(Cli::command)
+        .var(complete::COMPLETE_VAR)
+        .complete();
+
     let cli = Cli::parse();
 
-    let result = match cli.command {
-        Commands::Add { path } => commands::add::run(&path),
+    if cli.no_pager {
+        git::disable_pager();
+    }
+
+    match run(cli.command) {
+        Ok(()) => ExitCode::SUCCESS,
+        // Git already printed its own error; just mirror its exit status
+        Err(Error::GitExited(code)) => ExitCode::from(u8::try_from(code).unwrap_or(1)),
+        Err(e) => {
+            eprintln!("{} {e}", "error:".red().bold());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Run the chosen subcommand.
+fn run(command: Commands) -> Result<()> {
+    match command {
+        Commands::Add { patch: true, path } => commands::add::patch(path.as_deref()),
+        Commands::Add { patch: false, path } => {
+            commands::add::run(&path.unwrap_or_default())
+        }
         Commands::Rm { path } => commands::rm::run(&path),
+        Commands::Prune { yes } => commands::prune::run(yes),
         Commands::Status => commands::status::run(),
-        Commands::Commit { message } => commands::commit::run(&message),
-        Commands::Log { args } => commands::log::run(&args),
+        Commands::Commit {
+            message,
+            all,
+            amend,
+            template,
+            paths,
+        } => {
+            let source = match (message, template) {
+                (Some(message), _) => MessageSource::Literal(message),
+                (None, Some(template)) => {
+                    MessageSource::Template(Some(template).filter(|t| !t.is_empty()))
+                }
+                (None, None) => MessageSource::Editor,
+            };
+            commands::commit::run(source, &paths, all, amend)
+        }
+        Commands::Log { main, args } => commands::log::run(main.as_deref(), &args),
+        Commands::Show { at, args } => commands::show::run(at.as_deref(), &args),
         Commands::LsFiles { args } => commands::ls_files::run(&args),
-        Commands::Auto => commands::auto::run(),
-        Commands::Init { path } => commands::init::run(path.as_deref()),
+        Commands::Auto {
+            background,
+            quiet,
+            no_push,
+            detached,
+        } => commands::auto::run(AutoOptions {
+            mode: if detached {
+                RunMode::Detached
+            } else if background {
+                RunMode::Background
+            } else {
+                RunMode::Foreground
+            },
+            quiet,
+            no_push,
+        }),
+        Commands::Init {
+            path,
+            central,
+            relocate,
+            per_branch,
+            no_per_branch,
+            message_template,
+        } => {
+            let per_branch = (per_branch || no_per_branch).then_some(per_branch);
+            commands::init::run(
+                path.as_deref(),
+                central,
+                relocate,
+                per_branch,
+                message_template.as_deref(),
+            )
+        }
         Commands::Hook { action } => match action {
-            HookAction::Install { on } => commands::hook::install(&on),
-            HookAction::Uninstall { on } => commands::hook::uninstall(&on),
+            HookAction::Install { on } => commands::hook::install(on),
+            HookAction::Uninstall { on } => commands::hook::uninstall(on),
+            HookAction::List => commands::hook::list(),
+            HookAction::Log { count, failures } => commands::hook::log(count, failures),
+            HookAction::Run {
+                kind,
+                detached,
+                args,
+            } => commands::hook::run(kind, &args, detached),
         },
         Commands::Info => commands::info::run(),
+        Commands::Completions { shell } => commands::completions::run(shell),
+        Commands::InstallDocs { dir } => commands::install_docs::run(Cli::command(), dir.as_deref()),
         Commands::Remote { args } => commands::remote::run(&args),
-        Commands::Push => commands::push::run(),
-        Commands::Pull => commands::pull::run(),
-    };
-
-    match result {
-        Ok(()) => ExitCode::SUCCESS,
-        Err(e) => {
-            eprintln!("{} {e}", "error:".red().bold());
-            ExitCode::FAILURE
-        }
+        Commands::Git { args } => commands::exec::run(&args),
+        Commands::Clone { url, no_verify } => commands::clone::run(&url, no_verify),
+        Commands::Push { force } => commands::push::run(force),
+        Commands::Pull { from } => commands::pull::run(from.as_deref()),
+        Commands::Archive {
+            rev,
+            at,
+            format,
+            manifest,
+            output,
+        } => commands::archive::run(&ArchiveOptions {
+            rev: &rev,
+            at: at.as_deref(),
+            format,
+            manifest,
+            output: &output,
+        }),
+        Commands::Bundle { action } => match action {
+            BundleAction::Create { file, full } => commands::bundle::create(&file, full),
+            BundleAction::Apply { file, force } => commands::bundle::apply(&file, force),
+        },
     }
 }
@@ -0,0 +1,187 @@
+use crate::error::{Error, Result};
+use crate::git;
+use crate::settings;
+use crate::side_repo::{MAIN_COMMIT_TRAILER, SideRepo};
+use crate::timestamp;
+
+/// Template used when none is configured: the main repo's last commit message.
+pub const DEFAULT_TEMPLATE: &str = "{message}";
+
+/// Template used when the main message would duplicate an earlier side commit.
+pub const SUMMARY_TEMPLATE: &str = "{summary}";
+
+/// Side repo config key holding the per-project template.
+pub const TEMPLATE_KEY: &str = "side.messageTemplate";
+
+/// Show at most this many file names per group in a generated summary.
+const SUMMARY_MAX_NAMES: usize = 3;
+
+/// Values available to message templates.
+pub struct MessageContext {
+    /// Full last commit message of the main repo.
+    pub message: String,
+    /// First line of the main repo's last commit message.
+    pub subject: String,
+    /// Short SHA of the main repo's `HEAD`.
+    pub short_sha: String,
+    /// Current main repo branch (`HEAD` when detached).
+    pub branch: String,
+    /// Staged side changes as `(status, path)` pairs, excluding the `.side-tracked` manifest.
+    pub changes: Vec<(char, String)>,
+    /// Current UTC time.
+    pub timestamp: String,
+}
+
+impl MessageContext {
+    /// Gather template values from the main repo and the side repo's index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the side repo's staged changes cannot be listed.
+    pub fn gather(repo: &SideRepo) -> Result<Self> {
+        let message = git::last_commit_message().unwrap_or_default();
+        let subject = message.lines().next().unwrap_or_default().to_string();
+        let short_sha = git::head_sha()
+            .map(|sha| sha[..7.min(sha.len())].to_string())
+            .unwrap_or_default();
+        let branch = git::current_branch()?.unwrap_or_else(|| "HEAD".to_string());
+
+        let diff = repo.git(&["diff", "--cached", "--name-status", "--no-renames"])?;
+        let changes = diff
+            .lines()
+            .filter_map(|line| {
+                let (status, path) = line.split_once('\t')?;
+                // The manifest changes alongside tracked paths; it is not news on its own
+                if path == ".side-tracked" {
+                    return None;
+                }
+                Some((status.chars().next()?, path.to_string()))
+            })
+            .collect();
+
+        Ok(Self {
+            message,
+            subject,
+            short_sha,
+            branch,
+            changes,
+            timestamp: timestamp::now(),
+        })
+    }
+
+    /// Describe the staged changes, e.g. `Update NOTES.md, TODO.md; add scratch/a.py`.
+    #[must_use]
+    pub fn summary(&self) -> String {
+        let groups = [('M', "update"), ('A', "add"), ('D', "delete")];
+        let parts: Vec<String> = groups
+            .iter()
+            .filter_map(|(status, verb)| {
+                let names: Vec<&str> = self
+                    .changes
+                    .iter()
+                    .filter(|(s, _)| s == status)
+                    .map(|(_, p)| p.as_str())
+                    .collect();
+                match names.len() {
+                    0 => None,
+                    n if n > SUMMARY_MAX_NAMES => Some(format!("{verb} {n} files")),
+                    _ => Some(format!("{verb} {}", names.join(", "))),
+                }
+            })
+            .collect();
+
+        let summary = parts.join("; ");
+        let mut chars = summary.chars();
+        chars.next().map_or_else(
+            || "Update side-tracked files".to_string(),
+            |first| first.to_uppercase().chain(chars).collect(),
+        )
+    }
+
+    /// Render a template, substituting `{message}`, `{subject}`, `{sha}`, `{branch}`,
+    /// `{files}`, `{count}`, `{timestamp}` and `{summary}`.
+    ///
+    /// The template is scanned once, so braces inside substituted values (a main commit
+    /// message mentioning `{files}`, say) are kept as they are. Unknown names stay literal.
+    #[must_use]
+    pub fn render(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            let token = &rest[start..];
+            let value = token
+                .find('}')
+                .and_then(|end| Some((self.value(&token[1..end])?, end)));
+            if let Some((value, end)) = value {
+                rendered.push_str(&value);
+                rest = &token[end + 1..];
+            } else {
+                rendered.push('{');
+                rest = &token[1..];
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+
+    /// Value of the placeholder `name`, if it is one.
+    fn value(&self, name: &str) -> Option<String> {
+        let paths = || self.changes.iter().map(|(_, p)| p.as_str());
+        Some(match name {
+            "message" => self.message.trim_end().to_string(),
+            "subject" => self.subject.clone(),
+            "sha" => self.short_sha.clone(),
+            "branch" => self.branch.clone(),
+            "files" => paths().collect::<Vec<_>>().join(", "),
+            "count" => paths().count().to_string(),
+            "timestamp" => self.timestamp.clone(),
+            "summary" => self.summary(),
+            _ => return None,
+        })
+    }
+}
+
+/// The configured template (side repo, then main repo git config), if any.
+#[must_use]
+pub fn configured_template(repo: &SideRepo) -> Option<String> {
+    settings::get(repo, TEMPLATE_KEY, None).ok().flatten()
+}
+
+/// Build the message for `auto`.
+///
+/// Without a configured template the main repo's last message is used, unless a side
+/// commit was already recorded for the same main commit; then a summary of the side
+/// diff is used instead to avoid duplicates.
+///
+/// # Errors
+///
+/// Returns an error if the context cannot be gathered or the message renders empty.
+pub fn for_auto(repo: &SideRepo) -> Result<String> {
+    let context = MessageContext::gather(repo)?;
+
+    let configured = configured_template(repo);
+    let template = configured.clone().unwrap_or_else(|| {
+        if already_recorded(repo) {
+            SUMMARY_TEMPLATE.to_string()
+        } else {
+            DEFAULT_TEMPLATE.to_string()
+        }
+    });
+
+    let message = context.render(&template);
+    if message.trim().is_empty() {
+        return Err(configured.map_or_else(
+            || Error::GitCommandFailed("no commit message found in main repo".to_string()),
+            Error::EmptyMessage,
+        ));
+    }
+    Ok(message)
+}
+
+/// Whether the side `HEAD` was already committed against the main repo's current `HEAD`.
+fn already_recorded(repo: &SideRepo) -> bool {
+    let format = format!("--format=%(trailers:key={MAIN_COMMIT_TRAILER},valueonly)");
+    let recorded = repo.git(&["log", "-1", &format]).unwrap_or_default();
+    git::head_sha().is_some_and(|sha| recorded.trim() == sha)
+}
@@ -0,0 +1,128 @@
+use crate::error::{Error, Result};
+use crate::git;
+use crate::side_repo::{DEFAULT_BRANCH, SideRepo};
+
+/// Whether `auto` pushes after committing (default: true).
+pub const AUTO_PUSH_KEY: &str = "side.autoPush";
+
+/// Remote pulled from and set up by `clone` (default: `origin`).
+pub const REMOTE_KEY: &str = "side.remote";
+
+/// Remotes `push` and `auto` push to; multi-valued or comma-separated (default: all remotes).
+pub const PUSH_REMOTE_KEY: &str = "side.pushRemote";
+
+/// Side branch created on init and pushed to / pulled from (default: `main`).
+pub const BRANCH_KEY: &str = "side.branch";
+
+/// Largest file `add` and `auto` will stage, in bytes; `k`, `m` and `g` suffixes work (default: no limit).
+pub const MAX_FILE_SIZE_KEY: &str = "side.maxFileSize";
+
+/// Remote used when `side.remote` is unset.
+pub const DEFAULT_REMOTE: &str = "origin";
+
+/// git-side settings, read from `side.*` git config keys.
+///
+/// Each key is looked up in the side repo's own config first, then in the main
+/// repo's config (local, global and system, resolved as git does).
+#[derive(Debug, Clone)]
+pub struct Settings {
+    /// Push after `auto` commits.
+    pub auto_push: bool,
+    /// Remote to pull from.
+    pub remote: String,
+    /// Remotes to push to; empty means every configured remote.
+    pub push_remotes: Vec<String>,
+    /// Side branch to push and pull.
+    pub branch: String,
+    /// Files larger than this many bytes are not staged.
+    pub max_file_size: Option<u64>,
+}
+
+impl Settings {
+    /// Read all settings for `repo`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSetting` if a value cannot be parsed (e.g. `side.autoPush = maybe`).
+    pub fn load(repo: &SideRepo) -> Result<Self> {
+        let max_file_size = match get(repo, MAX_FILE_SIZE_KEY, Some("int"))? {
+            Some(value) => {
+                let bytes = value.parse::<u64>().map_err(|_| Error::InvalidSetting {
+                    key: MAX_FILE_SIZE_KEY.to_string(),
+                    reason: format!("'{value}' is not a size"),
+                })?;
+                // 0 means no limit
+                Some(bytes).filter(|b| *b > 0)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            auto_push: get(repo, AUTO_PUSH_KEY, Some("bool"))?.is_none_or(|v| v == "true"),
+            remote: get(repo, REMOTE_KEY, None)?.unwrap_or_else(|| DEFAULT_REMOTE.to_string()),
+            push_remotes: get_all(repo, PUSH_REMOTE_KEY)?,
+            branch: get(repo, BRANCH_KEY, None)?.unwrap_or_else(|| DEFAULT_BRANCH.to_string()),
+            max_file_size,
+        })
+    }
+}
+
+/// Look up a single `side.*` key: the side repo's config first, then the main repo's.
+/// `kind` is a git config `--type` (`bool`, `int`, ...) used to normalise the value.
+///
+/// # Errors
+///
+/// Returns `InvalidSetting` if git cannot interpret the value as `kind`.
+pub fn get(repo: &SideRepo, key: &str, kind: Option<&str>) -> Result<Option<String>> {
+    let type_arg = kind.map(|k| format!("--type={k}"));
+    let mut args = vec!["config"];
+    args.extend(type_arg.as_deref());
+    args.extend(["--get", key]);
+
+    if repo.is_initialized() {
+        let mut side_args = args.clone();
+        side_args.insert(1, "--worktree");
+        if let Some(value) = lookup(key, repo.git(&side_args))? {
+            return Ok(Some(value));
+        }
+    }
+    lookup(key, git::run(&args))
+}
+
+/// Look up a multi-valued `side.*` key: the side repo's values if it has any, otherwise
+/// the main repo's. Values may also hold comma- or space-separated lists.
+///
+/// # Errors
+///
+/// Returns an error if git config cannot be read.
+pub fn get_all(repo: &SideRepo, key: &str) -> Result<Vec<String>> {
+    let side = if repo.is_initialized() {
+        lookup(key, repo.git(&["config", "--worktree", "--get-all", key]))?
+    } else {
+        None
+    };
+    let values = match side {
+        Some(values) => Some(values),
+        None => lookup(key, git::run(&["config", "--get-all", key]))?,
+    };
+
+    Ok(values
+        .unwrap_or_default()
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Interpret the result of `git config --get`: exit status 1 without output means unset.
+fn lookup(key: &str, result: Result<String>) -> Result<Option<String>> {
+    match result {
+        Ok(value) => Ok(Some(value).filter(|v| !v.is_empty())),
+        Err(Error::GitCommandFailed(stderr)) if stderr.is_empty() => Ok(None),
+        Err(Error::GitCommandFailed(stderr)) => Err(Error::InvalidSetting {
+            key: key.to_string(),
+            reason: stderr.trim_start_matches("fatal: ").to_string(),
+        }),
+        Err(e) => Err(e),
+    }
+}
@@ -1,18 +1,78 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::config::{self, hash_path};
 use crate::error::{Error, Result};
 use crate::git;
+use crate::settings::{self, Settings};
+use crate::timestamp;
+use crate::tracked::TrackedPaths;
+
+/// Branch used by the side repo when not following the main repo's branches.
+pub const DEFAULT_BRANCH: &str = "main";
+
+/// Commit trailer linking a side commit to the main repo's `HEAD` at commit time.
+pub const MAIN_COMMIT_TRAILER: &str = "Main-Commit";
+
+/// Side repo config keys recording which project the repo belongs to.
+const ROOT_SHA_KEY: &str = "side.rootSha";
+const PROJECT_PATH_KEY: &str = "side.projectPath";
+const CREATED_WITH_KEY: &str = "side.createdWith";
+const CREATED_AT_KEY: &str = "side.createdAt";
+
+/// Side repo config section for state git-side records, kept apart from `side.*` settings.
+const STATE_SECTION: &str = "side-state";
+/// Keys (`side-state.<remote>.<key>`) recording the outcome of the last push.
+const LAST_PUSH_AT_KEY: &str = "lastPushAt";
+const LAST_PUSH_ERROR_KEY: &str = "lastPushError";
+
+/// Outcome of the most recent push to one remote, as recorded in the side repo.
+#[derive(Debug)]
+pub struct PushStatus {
+    /// Remote pushed to.
+    pub remote: String,
+    /// When the push was attempted (RFC 3339, UTC).
+    pub at: String,
+    /// Why it failed, or `None` if it succeeded.
+    pub error: Option<String>,
+}
+
+/// Project metadata stored in the side repo's config when it is created.
+#[derive(Debug, Default)]
+pub struct ProjectIdentity {
+    /// Root commit SHA of the main repo the side repo belongs to.
+    pub root_sha: Option<String>,
+    /// Work tree the side repo was created for.
+    pub path: Option<PathBuf>,
+    /// git-side version that created the side repo.
+    pub created_with: Option<String>,
+    /// Creation time (RFC 3339, UTC).
+    pub created_at: Option<String>,
+}
+
+/// Options for [`SideRepo::commit_with`].
+#[derive(Debug, Default)]
+pub struct CommitOptions<'a> {
+    /// Commit message; `None` opens the editor.
+    pub message: Option<&'a str>,
+    /// Only commit these paths, taking their current work tree state.
+    pub paths: &'a [PathBuf],
+    /// Replace the tip commit instead of creating a new one.
+    pub amend: bool,
+}
 
 /// Represents a side repository for a project.
 pub struct SideRepo {
     /// Path to the bare git repository.
+    /// In central storage, the project's administrative directory inside the shared repo.
     pub git_dir: PathBuf,
     /// Path to the work tree (the main project directory).
     pub work_tree: PathBuf,
     /// The initial commit SHA of the main repo (project identifier).
     pub root_sha: String,
+    /// The shared bare repo holding this project, when using central storage.
+    pub central: Option<PathBuf>,
 }
 
 impl SideRepo {
@@ -20,13 +80,17 @@ impl SideRepo {
     ///
     /// # Errors
     ///
-    /// Returns an error if not in a git repository or if config files cannot be accessed.
+    /// Returns an error if not in a git repository, if config files cannot be accessed,
+    /// or `ProjectMismatch` if the side repo found belongs to another project.
     pub fn open() -> Result<Self> {
         let work_tree = git::repo_root()?;
         let path_hash = hash_path(&work_tree);
 
-        // Try cache first
-        let root_sha = if let Some(sha) = config::cache_lookup(&path_hash)? {
+        // Try cache first; a cached root that is not in this repo means the path
+        // now holds a different project
+        let root_sha = if let Some(sha) = config::cache_lookup(&path_hash)?
+            && git::has_commit(&sha)
+        {
             sha
         } else {
             // Cache miss: resolve and store
@@ -35,17 +99,104 @@ impl SideRepo {
             sha
         };
 
-        // Get base path (custom or default)
-        let base_path = config::paths_lookup(&root_sha)?
-            .unwrap_or_else(config::default_base_path);
+        let repo = if let Some(central) = config::central_lookup(&root_sha)? {
+            Self::in_central(work_tree, root_sha, central)
+        } else {
+            // Get base path (custom, git config or default)
+            let base_path = config::base_path(&root_sha)?;
 
-        let git_dir = base_path.join(&root_sha);
+            Self {
+                git_dir: base_path.join(&root_sha),
+                work_tree,
+                root_sha,
+                central: None,
+            }
+        };
+        repo.verify_identity()?;
+        Ok(repo)
+    }
 
-        Ok(Self {
-            git_dir,
+    /// A project stored in the shared bare repo at `central`.
+    ///
+    /// Each project gets a linked-worktree style directory (`worktrees/<root_sha>`)
+    /// with its own `HEAD`, index and config, while objects and refs are shared.
+    /// Its branches live under `refs/projects/<root_sha>/`.
+    #[must_use]
+    pub fn in_central(work_tree: PathBuf, root_sha: String, central: PathBuf) -> Self {
+        Self {
+            git_dir: central.join("worktrees").join(&root_sha),
             work_tree,
             root_sha,
-        })
+            central: Some(central),
+        }
+    }
+
+    /// Ref prefix under which this project's branches live.
+    #[must_use]
+    pub fn ref_prefix(&self) -> String {
+        if self.central.is_some() {
+            format!("refs/projects/{}/", self.root_sha)
+        } else {
+            "refs/heads/".to_string()
+        }
+    }
+
+    /// Full ref name of one of this project's side branches.
+    #[must_use]
+    pub fn branch_ref(&self, branch: &str) -> String {
+        format!("{}{branch}", self.ref_prefix())
+    }
+
+    /// Remote-tracking ref for this project's side `branch` on `remote`.
+    #[must_use]
+    pub fn upstream_ref(&self, remote: &str, branch: &str) -> String {
+        if self.central.is_some() {
+            format!("refs/remotes/{remote}/projects/{}/{branch}", self.root_sha)
+        } else {
+            format!("refs/remotes/{remote}/{branch}")
+        }
+    }
+
+    /// Read the project metadata recorded in the side repo.
+    #[must_use]
+    pub fn identity(&self) -> ProjectIdentity {
+        ProjectIdentity {
+            root_sha: self.config_get(ROOT_SHA_KEY),
+            path: self.config_get(PROJECT_PATH_KEY).map(PathBuf::from),
+            created_with: self.config_get(CREATED_WITH_KEY),
+            created_at: self.config_get(CREATED_AT_KEY),
+        }
+    }
+
+    /// Record the project metadata in the side repo's config.
+    fn record_identity(&self) -> Result<()> {
+        let work_tree = self.work_tree.to_string_lossy();
+        self.config_set(ROOT_SHA_KEY, &self.root_sha)?;
+        self.config_set(PROJECT_PATH_KEY, &work_tree)?;
+        self.config_set(CREATED_WITH_KEY, env!("CARGO_PKG_VERSION"))?;
+        self.config_set(CREATED_AT_KEY, &timestamp::now())
+    }
+
+    /// Check that an existing side repo was created for this project.
+    /// Side repos created before identities were recorded adopt the current one.
+    fn verify_identity(&self) -> Result<()> {
+        if !self.is_initialized() {
+            return Ok(());
+        }
+
+        match self.config_get(ROOT_SHA_KEY) {
+            Some(found) if found != self.root_sha => Err(Error::ProjectMismatch {
+                git_dir: self.git_dir.clone(),
+                expected: self.root_sha.clone(),
+                found,
+            }),
+            Some(_) => Ok(()),
+            // Best effort: a read-only side repo still works, it just stays unlabelled
+            None => {
+                let _ = self.record_identity();
+                Ok(())
+            }
+        }
     }
 
     /// Check if the side repo has been initialized.
@@ -64,6 +215,12 @@ impl SideRepo {
             return Ok(());
         }
 
+        let branch = Settings::load(self)?.branch;
+        if let Some(central) = &self.central {
+            self.init_in_central(central, &branch)?;
+            return self.record_identity();
+        }
+
         // Create parent directory
         if let Some(parent) = self.git_dir.parent() {
             fs::create_dir_all(parent).map_err(|e| Error::CreateDir {
@@ -73,9 +230,45 @@ impl SideRepo {
         }
 
         // Initialize bare repo
-        let git_dir_str = self.git_dir.to_string_lossy();
-        git::run(&["init", "--bare", &git_dir_str])?;
+        git::run_bare(&self.git_dir, &["init", "--bare"])?;
+
+        // Pin the initial branch regardless of the user's init.defaultBranch
+        let head = self.branch_ref(&branch);
+        self.git(&["symbolic-ref", "HEAD", &head])?;
+
+        self.record_identity()
+    }
+
+    /// Create the shared repo if needed and register this project's directory in it.
+    fn init_in_central(&self, central: &Path, branch: &str) -> Result<()> {
+        if !central.join("HEAD").exists() {
+            fs::create_dir_all(central).map_err(|e| Error::CreateDir {
+                path: central.to_path_buf(),
+                source: e,
+            })?;
+            git::run_bare(central, &["init", "--bare"])?;
+            // Per-project settings go to each project's config.worktree
+            git::run_bare(central, &["config", "core.repositoryFormatVersion", "1"])?;
+            git::run_bare(central, &["config", "extensions.worktreeConfig", "true"])?;
+        }
+
+        fs::create_dir_all(&self.git_dir).map_err(|e| Error::CreateDir {
+            path: self.git_dir.clone(),
+            source: e,
+        })?;
 
+        // No `gitdir` file: the project has no `.git` file of its own for it to point at,
+        // and pointing it at the main repo would let `git worktree repair` rewrite that
+        let files = [
+            ("commondir", "../..\n".to_string()),
+            // Keep `git worktree prune` from discarding the project
+            ("locked", "managed by git-side\n".to_string()),
+            ("HEAD", format!("ref: {}\n", self.branch_ref(branch))),
+        ];
+        for (name, content) in files {
+            let path = self.git_dir.join(name);
+            fs::write(&path, content).map_err(|e| Error::WriteFile { path, source: e })?;
+        }
         Ok(())
     }
 
@@ -109,6 +302,10 @@ impl SideRepo {
     /// Stage paths with update flag (handles modifications and deletions).
     /// Errors are ignored since paths may not be in the index yet.
     pub fn stage_update(&self, paths: &[PathBuf]) {
+        self.stage_update_excluding(paths, &[]);
+    }
+
+    fn stage_update_excluding(&self, paths: &[PathBuf], exclude: &[String]) {
         if paths.is_empty() {
             return;
         }
@@ -116,9 +313,8 @@ impl SideRepo {
             return;
         }
 
-        let path_strs: Vec<String> = paths.iter().map(|p| p.to_string_lossy().into_owned()).collect();
-        let mut args: Vec<&str> = vec!["add", "-f", "-u", "--"];
-        args.extend(path_strs.iter().map(String::as_str));
+        let args = add_args(&["-f", "-u"], paths, exclude);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
         // Ignore errors — paths may not be in the index yet
         let _ = self.git(&args);
@@ -130,63 +326,249 @@ impl SideRepo {
     ///
     /// Returns an error if initialization or staging fails.
     pub fn stage_new(&self, paths: &[PathBuf]) -> Result<()> {
+        self.stage_new_excluding(paths, &[])
+    }
+
+    fn stage_new_excluding(&self, paths: &[PathBuf], exclude: &[String]) -> Result<()> {
         if paths.is_empty() {
             return Ok(());
         }
         self.ensure_initialized()?;
 
-        let path_strs: Vec<String> = paths.iter().map(|p| p.to_string_lossy().into_owned()).collect();
-        let mut args: Vec<&str> = vec!["add", "-f", "--"];
-        args.extend(path_strs.iter().map(String::as_str));
+        let args = add_args(&["-f"], paths, exclude);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
 
         self.git(&args)?;
         Ok(())
     }
 
+    /// Stage the current state of `paths`: modifications, deletions and new files.
+    ///
+    /// Files larger than `limit` bytes are left out. Their size is checked on disk
+    /// before staging, so their content never reaches the side object store.
+    /// Returns the files left out, with their sizes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if initialization or staging fails.
+    pub fn stage_paths(&self, paths: &[PathBuf], limit: Option<u64>) -> Result<Vec<(PathBuf, u64)>> {
+        let oversized: Vec<(PathBuf, u64)> = limit.map_or_else(Vec::new, |limit| {
+            paths
+                .iter()
+                .flat_map(|path| TrackedPaths::expand_path(&self.work_tree, path))
+                .filter_map(|path| {
+                    let size = fs::metadata(self.work_tree.join(&path)).ok()?.len();
+                    (size > limit).then_some((path, size))
+                })
+                .collect()
+        });
+        let exclude: Vec<String> = oversized
+            .iter()
+            .map(|(path, _)| format!(":(exclude,literal){}", path.to_string_lossy()))
+            .collect();
+
+        self.stage_update_excluding(paths, &exclude);
+
+        // Deleted paths were handled by the update pass; `add` would reject them
+        let existing: Vec<PathBuf> = paths
+            .iter()
+            .filter(|p| self.work_tree.join(p).exists())
+            .cloned()
+            .collect();
+        self.stage_new_excluding(&existing, &exclude)?;
+        Ok(oversized)
+    }
+
+    /// Interactively stage hunks from `paths` (`git add -p`), attached to the terminal.
+    ///
+    /// Files not in the side index yet are marked intent-to-add first so their content
+    /// can be staged too; the marks are removed again from files that end up with
+    /// nothing staged, so declined files are not picked up by the next commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if initialization fails or git exits with an error.
+    pub fn stage_patch(&self, paths: &[PathBuf]) -> Result<()> {
+        self.ensure_initialized()?;
+
+        let path_strs: Vec<String> = paths
+            .iter()
+            .filter(|p| self.work_tree.join(p).exists())
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        if path_strs.is_empty() {
+            return Ok(());
+        }
+
+        // Untracked files under the paths (no exclude rules: side tracking bypasses them)
+        let mut others_args: Vec<&str> = vec!["ls-files", "-z", "--others", "--"];
+        others_args.extend(path_strs.iter().map(String::as_str));
+        let others = self.git(&others_args)?;
+        let untracked: Vec<&str> = others.split('\0').filter(|p| !p.is_empty()).collect();
+
+        if !untracked.is_empty() {
+            let mut intent_args: Vec<&str> = vec!["add", "-f", "--intent-to-add", "--"];
+            intent_args.extend(&untracked);
+            self.git(&intent_args)?;
+        }
+
+        let mut patch_args: Vec<&str> = vec!["add", "--patch", "--"];
+        patch_args.extend(path_strs.iter().map(String::as_str));
+        let result = git::run_with_paths_interactive(&self.git_dir, &self.work_tree, &patch_args);
+
+        if !untracked.is_empty() {
+            // Intent-to-add entries do not show in `diff --cached`; staged content does
+            let mut staged_args: Vec<&str> = vec!["diff", "--cached", "--name-only", "-z", "--"];
+            staged_args.extend(&untracked);
+            let staged = self.git(&staged_args)?;
+            let staged: Vec<&str> = staged.split('\0').collect();
+
+            let declined: Vec<&str> = untracked
+                .iter()
+                .filter(|p| !staged.contains(p))
+                .copied()
+                .collect();
+            if !declined.is_empty() {
+                let mut rm_args: Vec<&str> = vec!["rm", "--cached", "--quiet", "--"];
+                rm_args.extend(&declined);
+                self.git(&rm_args)?;
+            }
+        }
+
+        result
+    }
+
     /// Commit staged changes.
     ///
     /// # Errors
     ///
     /// Returns `NothingToCommit` if there are no staged changes, or an error if commit fails.
     pub fn commit(&self, message: &str) -> Result<()> {
+        self.commit_with(&CommitOptions {
+            message: Some(message),
+            ..CommitOptions::default()
+        })
+    }
+
+    /// Commit with explicit options (message or editor, path limiting, amend).
+    ///
+    /// # Errors
+    ///
+    /// Returns `NothingToCommit` if there is nothing to record (unless amending),
+    /// or an error if the commit fails or the editor is aborted.
+    pub fn commit_with(&self, options: &CommitOptions<'_>) -> Result<()> {
         self.ensure_initialized()?;
 
+        let path_strs: Vec<String> = options
+            .paths
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+
         // Check if there's anything staged to commit
         // diff --cached --quiet exits with 1 if there are staged changes, 0 if none
-        let has_staged = self.git(&["diff", "--cached", "--quiet"]).is_err();
-        if !has_staged {
-            return Err(Error::NothingToCommit);
+        if !options.amend {
+            let mut diff_args = vec!["diff", "--cached", "--quiet", "--"];
+            diff_args.extend(path_strs.iter().map(String::as_str));
+            let has_staged = self.git(&diff_args).is_err();
+            if !has_staged {
+                return Err(Error::NothingToCommit);
+            }
+        }
+
+        // Record which main commit this side snapshot belongs to; on amend, replace it
+        let trailer = git::head_sha().map(|sha| format!("{MAIN_COMMIT_TRAILER}: {sha}"));
+        let mut args = vec!["-c", "trailer.ifexists=replace", "commit"];
+        if options.amend {
+            args.push("--amend");
+        }
+        if let Some(message) = options.message {
+            args.extend(["-m", message]);
+        }
+        if let Some(trailer) = &trailer {
+            args.extend(["--trailer", trailer.as_str()]);
+        }
+        if !path_strs.is_empty() {
+            args.push("--");
+            args.extend(path_strs.iter().map(String::as_str));
         }
 
-        self.git(&["commit", "-m", message])?;
+        if options.message.is_some() {
+            self.git(&args)?;
+        } else {
+            // No message: let git open the user's editor
+            git::run_with_paths_interactive(&self.git_dir, &self.work_tree, &args)?;
+        }
         Ok(())
     }
 
-    /// Get status output.
+    /// Find the side snapshot closest to a main repo revision: the most recent side
+    /// commit recorded against that main commit or, failing that, its nearest ancestor.
+    /// Returns `(side_sha, main_sha)` where `main_sha` is the main commit actually matched.
     ///
     /// # Errors
     ///
-    /// Returns an error if the git status command fails.
-    pub fn status(&self) -> Result<String> {
+    /// Returns an error if the revision cannot be resolved or git commands fail.
+    pub fn snapshot_for_main(&self, main_rev: &str) -> Result<Option<(String, String)>> {
+        let main_sha = git::resolve_commit(main_rev)?;
         if !self.is_initialized() {
-            return Ok(String::from("Side repo not initialized. Use 'git side add <path>' to start tracking files."));
+            return Ok(None);
+        }
+
+        let format = format!("--format=%H %(trailers:key={MAIN_COMMIT_TRAILER},valueonly,separator=%x20)");
+        // In central storage only this project's branches are relevant
+        let scope = if self.central.is_some() {
+            format!("--glob={}*", self.ref_prefix())
+        } else {
+            "--all".to_string()
+        };
+        let log = self.git(&["log", &scope, &format]).unwrap_or_default();
+
+        // Log is newest first, so keep the first side commit seen per main commit
+        let mut by_main: HashMap<&str, &str> = HashMap::new();
+        for line in log.lines() {
+            if let Some((side, main)) = line.split_once(' ')
+                && !main.trim().is_empty()
+            {
+                by_main.entry(main.trim()).or_insert(side);
+            }
+        }
+        if by_main.is_empty() {
+            return Ok(None);
         }
-        self.git(&["status"])
+
+        let ancestors = git::run(&["rev-list", &main_sha])?;
+        Ok(ancestors.lines().find_map(|main| {
+            by_main
+                .get(main)
+                .map(|side| ((*side).to_string(), main.to_string()))
+        }))
+    }
+
+    /// Run a git command in the context of the side repo, streaming to the terminal.
+    /// Git's pager and colors behave as for a native command.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the git command fails.
+    pub fn stream(&self, args: &[&str]) -> Result<()> {
+        git::run_with_paths_interactive(&self.git_dir, &self.work_tree, args)
     }
 
-    /// Get log output.
+    /// Stream log output.
     ///
     /// # Errors
     ///
     /// Returns an error if the git log command fails.
-    pub fn log(&self, args: &[&str]) -> Result<String> {
+    pub fn log(&self, args: &[&str]) -> Result<()> {
         if !self.is_initialized() {
-            return Ok(String::from("Side repo not initialized. No history yet."));
+            println!("Side repo not initialized. No history yet.");
+            return Ok(());
         }
 
         let mut log_args = vec!["log"];
         log_args.extend(args);
-        self.git(&log_args)
+        self.stream(&log_args)
     }
 
     /// Remove a path from the index (unstage).
@@ -204,31 +586,401 @@ impl SideRepo {
         Ok(())
     }
 
-    /// Force push to origin/main.
+    /// Check whether the configured remote exists.
+    #[must_use]
+    pub fn has_remote(&self, settings: &Settings) -> bool {
+        self.git(&["remote", "get-url", &settings.remote]).is_ok()
+    }
+
+    /// Check whether a local branch exists in the side repo.
+    #[must_use]
+    pub fn has_branch(&self, branch: &str) -> bool {
+        let refname = self.branch_ref(branch);
+        self.git(&["rev-parse", "--verify", "--quiet", &refname]).is_ok()
+    }
+
+    /// Get the branch the side repo's HEAD points to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the git command fails to execute.
+    pub fn current_branch(&self) -> Result<Option<String>> {
+        let prefix = self.ref_prefix();
+        Ok(self
+            .git(&["symbolic-ref", "-q", "HEAD"])
+            .ok()
+            .and_then(|r| r.strip_prefix(&prefix).map(str::to_string))
+            .filter(|b| !b.is_empty()))
+    }
+
+    /// Switch the side repo to `branch`, replacing side-tracked files in the work tree.
+    /// The branch's `.side-tracked` manifest becomes the active one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkout fails.
+    pub fn checkout(&self, branch: &str) -> Result<()> {
+        let stray = self.work_tree.join(".side-tracked");
+        let existed_before = stray.exists();
+
+        // Point HEAD at the branch, then make index and work tree match it
+        let refname = self.branch_ref(branch);
+        self.git(&["symbolic-ref", "HEAD", &refname])?;
+        self.git(&["reset", "--hard", "--quiet"])?;
+
+        self.reclaim_manifest(existed_before)
+    }
+
+    /// Move a `.side-tracked` manifest that a checkout wrote into the work tree back
+    /// into the git dir, where it is the active manifest. `existed_before` tells whether
+    /// the work tree already had such a file (then it belongs to the user and stays).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest cannot be moved.
+    pub fn reclaim_manifest(&self, existed_before: bool) -> Result<()> {
+        let stray = self.work_tree.join(".side-tracked");
+        if !existed_before && stray.is_file() {
+            fs::rename(&stray, self.tracked_file()).map_err(|e| Error::WriteFile {
+                path: self.tracked_file(),
+                source: e,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Point HEAD at `branch` without touching the work tree, creating the branch
+    /// from the current HEAD if needed. The index is reset to the branch tip so the
+    /// next staging pass records the work tree state on top of it.
     ///
     /// # Errors
     ///
-    /// Returns an error if the push fails (e.g. no remote configured).
-    pub fn push(&self) -> Result<()> {
-        self.git(&["push", "-u", "--force", "origin", "main"])?;
+    /// Returns an error if any git command fails.
+    pub fn attach_branch(&self, branch: &str) -> Result<()> {
+        let has_head = self.git(&["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok();
+        let refname = self.branch_ref(branch);
+        if !self.has_branch(branch) && has_head {
+            self.git(&["update-ref", &refname, "HEAD"])?;
+        }
+
+        self.git(&["symbolic-ref", "HEAD", &refname])?;
+
+        if self.has_branch(branch) {
+            self.git(&["reset", "--quiet"])?;
+        }
         Ok(())
     }
 
+    /// Read a value from the side repo's own git config.
+    /// Returns `None` if the key is unset.
+    #[must_use]
+    pub fn config_get(&self, key: &str) -> Option<String> {
+        if !self.is_initialized() {
+            return None;
+        }
+        // --worktree is the project's own config.worktree in central storage, --local otherwise
+        self.git(&["config", "--worktree", "--get", key])
+            .ok()
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Write a value to the side repo's own git config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if initialization or the git config command fails.
+    pub fn config_set(&self, key: &str, value: &str) -> Result<()> {
+        self.ensure_initialized()?;
+        self.git(&["config", "--worktree", key, value])?;
+        Ok(())
+    }
+
+    /// Remove a value from the side repo's own git config. Unset keys are ignored.
+    pub fn config_unset(&self, key: &str) {
+        if self.is_initialized() {
+            let _ = self.git(&["config", "--worktree", "--unset", key]);
+        }
+    }
+
+    /// Whether side commits follow the main repo's current branch.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InvalidSetting` if `side.perBranch` is not a boolean.
+    pub fn per_branch(&self) -> Result<bool> {
+        Ok(settings::get(self, "side.perBranch", Some("bool"))?.is_some_and(|v| v == "true"))
+    }
+
+    /// The side branch matching the main repo's current branch (per-branch mode).
+    /// Falls back to the configured side branch on a detached HEAD.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if not inside a git repository or settings are invalid.
+    pub fn branch_for_main(&self) -> Result<String> {
+        match git::current_branch()? {
+            Some(branch) => Ok(branch),
+            None => Ok(Settings::load(self)?.branch),
+        }
+    }
+
+    /// The side branch `pull` resets: the checked-out one in per-branch mode,
+    /// `side.branch` otherwise (or on a detached side HEAD).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `side.perBranch` is invalid or HEAD cannot be read.
+    pub fn sync_branch(&self, settings: &Settings) -> Result<String> {
+        if self.per_branch()?
+            && let Some(branch) = self.current_branch()?
+        {
+            return Ok(branch);
+        }
+        Ok(settings.branch.clone())
+    }
+
+    /// All remotes configured in the side repo.
+    #[must_use]
+    pub fn remotes(&self) -> Vec<String> {
+        self.git(&["remote"])
+            .map(|out| out.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remotes `push` and `auto` push to: `side.pushRemote` if set, otherwise all remotes.
+    #[must_use]
+    pub fn push_targets(&self, settings: &Settings) -> Vec<String> {
+        if settings.push_remotes.is_empty() {
+            self.remotes()
+        } else {
+            settings.push_remotes.clone()
+        }
+    }
+
+    /// Push the side branch to every push target, returning each remote's result.
+    ///
+    /// See [`SideRepo::push_to`] for what happens per remote.
+    #[must_use]
+    pub fn push(&self, settings: &Settings, force: bool) -> Vec<(String, Result<Vec<String>>)> {
+        self.push_targets(settings)
+            .into_iter()
+            .map(|remote| {
+                let result = self.push_to(&remote, settings, force);
+                (remote, result)
+            })
+            .collect()
+    }
+
+    /// Push the side branch to `remote`.
+    /// In per-branch mode every local side branch is pushed, since each main branch has its own.
+    /// In central storage, the branches of every project in the central repo are pushed.
+    ///
+    /// Without `force`, a remote that moved on (another machine pushed) is left alone
+    /// and `PushRejected` is returned; with `force` the local state overwrites it.
+    /// Other projects' branches are never forced: the refs the remote kept because
+    /// it has commits this machine lacks are returned instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `PushRejected` if the remote has diverged, or an error if the push fails
+    /// (e.g. the remote does not exist).
+    pub fn push_to(&self, remote: &str, settings: &Settings, force: bool) -> Result<Vec<String>> {
+        let result = if self.central.is_some() {
+            self.push_central(remote, force)
+        } else {
+            self.push_branches(remote, settings, force).map(|()| Vec::new())
+        };
+        self.record_push(remote, result.as_ref().err());
+        result
+    }
+
+    /// Push the side branch (every side branch in per-branch mode).
+    fn push_branches(&self, remote: &str, settings: &Settings, force: bool) -> Result<()> {
+        let mut args = vec!["push"];
+        if force {
+            args.push("--force");
+        }
+        // Only the pull remote becomes the branches' upstream
+        if remote == settings.remote {
+            args.push("-u");
+        }
+        let branch = if self.per_branch()? {
+            "refs/heads/*:refs/heads/*"
+        } else {
+            settings.branch.as_str()
+        };
+        args.extend([remote, branch]);
+
+        if self.run_push(&args)?.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::PushRejected {
+                remote: remote.to_string(),
+            })
+        }
+    }
+
+    /// Push every project's branches in one go; `force` only applies to this project's.
+    /// Returns the other projects' refs the remote rejected.
+    fn push_central(&self, remote: &str, force: bool) -> Result<Vec<String>> {
+        let prefix = self.ref_prefix();
+        if force {
+            let own = format!("+{prefix}*:{prefix}*");
+            self.run_push(&["push", remote, &own])?;
+        }
+
+        let rejected = self.run_push(&["push", remote, "refs/projects/*:refs/projects/*"])?;
+        let (own, others): (Vec<String>, Vec<String>) =
+            rejected.into_iter().partition(|r| r.starts_with(&prefix));
+        if own.is_empty() {
+            Ok(others)
+        } else {
+            Err(Error::PushRejected {
+                remote: remote.to_string(),
+            })
+        }
+    }
+
+    /// Run `git push`, returning the refs the remote rejected as not fast-forward.
+    fn run_push(&self, args: &[&str]) -> Result<Vec<String>> {
+        // Rejections are recognised by git's English wording
+        match git::run_with_paths_untranslated(&self.git_dir, &self.work_tree, args) {
+            Ok(_) => Ok(Vec::new()),
+            Err(Error::GitCommandFailed(stderr)) => {
+                let rejected = rejected_refs(&stderr);
+                if rejected.is_empty() {
+                    Err(Error::GitCommandFailed(stderr))
+                } else {
+                    Ok(rejected)
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Remember the outcome of a push. Best effort: a push is never failed over this.
+    fn record_push(&self, remote: &str, error: Option<&Error>) {
+        let at_key = state_key(remote, LAST_PUSH_AT_KEY);
+        let error_key = state_key(remote, LAST_PUSH_ERROR_KEY);
+        let _ = self.config_set(&at_key, &timestamp::now());
+        match error {
+            None => self.config_unset(&error_key),
+            Some(e) => {
+                let _ = self.config_set(&error_key, &push_error_summary(e));
+            }
+        }
+    }
+
+    /// Outcome of the most recent push to each remote that is still configured.
+    #[must_use]
+    pub fn last_pushes(&self) -> Vec<PushStatus> {
+        self.remotes()
+            .into_iter()
+            .filter_map(|remote| {
+                let at = self.config_get(&state_key(&remote, LAST_PUSH_AT_KEY))?;
+                let error = self.config_get(&state_key(&remote, LAST_PUSH_ERROR_KEY));
+                Some(PushStatus { remote, at, error })
+            })
+            .collect()
+    }
+
+    /// Fetch from the configured remote.
+    /// In central storage, every project's branches are fetched in one go.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the fetch fails (e.g. no remote configured).
+    pub fn fetch(&self, settings: &Settings) -> Result<()> {
+        if self.central.is_some() {
+            let refspec = format!("+refs/projects/*:refs/remotes/{}/projects/*", settings.remote);
+            self.git(&["fetch", &settings.remote, &refspec])?;
+        } else {
+            self.git(&["fetch", &settings.remote])?;
+        }
+        Ok(())
+    }
+
+    /// Fetch and reset the side branch (see [`SideRepo::sync_branch`]) to its remote
+    /// counterpart (remote wins, no conflicts).
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoRemoteBranch` if the remote lacks the branch, or an error if the
+    /// fetch or reset fails.
+    pub fn pull(&self, settings: &Settings) -> Result<()> {
+        self.fetch(settings)?;
+        let (_, upstream) = self.fetched_upstream(settings)?;
+        self.git(&["reset", "--hard", &upstream])?;
+        Ok(())
+    }
+
+    /// Fetch and fast-forward the side branch (see [`SideRepo::sync_branch`]) to its
+    /// remote counterpart. Local side commits are never dropped: a branch that is
+    /// ahead is left alone. Returns whether the branch moved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `NoRemoteBranch` if the remote lacks the branch, `SideDiverged` if both
+    /// sides have commits the other lacks, or an error if the fetch or merge fails
+    /// (e.g. it would overwrite files in the work tree).
+    pub fn fast_forward(&self, settings: &Settings) -> Result<bool> {
+        self.fetch(settings)?;
+        let (branch, upstream) = self.fetched_upstream(settings)?;
+
+        // On an unborn branch the merge simply checks out the remote state
+        if self.git(&["rev-parse", "--verify", "--quiet", "HEAD"]).is_ok() {
+            let is_ancestor = |ancestor: &str, descendant: &str| {
+                self.git(&["merge-base", "--is-ancestor", ancestor, descendant])
+                    .is_ok()
+            };
+            if is_ancestor(&upstream, "HEAD") {
+                return Ok(false);
+            }
+            if !is_ancestor("HEAD", &upstream) {
+                return Err(Error::SideDiverged {
+                    remote: settings.remote.clone(),
+                    branch,
+                });
+            }
+        }
+
+        let stray_existed = self.work_tree.join(".side-tracked").exists();
+        self.git(&["merge", "--ff-only", "--quiet", &upstream])?;
+        self.reclaim_manifest(stray_existed)?;
+        Ok(true)
+    }
+
+    /// The side branch `pull` works on, with its fetched remote-tracking ref.
+    fn fetched_upstream(&self, settings: &Settings) -> Result<(String, String)> {
+        let branch = self.sync_branch(settings)?;
+        let upstream = self.upstream_ref(&settings.remote, &branch);
+        if self
+            .git(&["rev-parse", "--verify", "--quiet", &upstream])
+            .is_err()
+        {
+            return Err(Error::NoRemoteBranch {
+                remote: settings.remote.clone(),
+                branch,
+            });
+        }
+        Ok((branch, upstream))
+    }
+
     /// List files in the side repo's index. Mirrors `git ls-files`.
     ///
-    /// Returns an empty string if the side repo is not initialized,
+    /// Prints nothing if the side repo is not initialized,
     /// so scripting consumers can pipe safely on fresh clones.
     ///
     /// # Errors
     ///
     /// Returns an error if the underlying `git ls-files` command fails.
-    pub fn ls_files(&self, args: &[&str]) -> Result<String> {
+    pub fn ls_files(&self, args: &[&str]) -> Result<()> {
         if !self.is_initialized() {
-            return Ok(String::new());
+            return Ok(());
         }
         let mut ls_args = vec!["ls-files"];
         ls_args.extend(args);
-        self.git(&ls_args)
+        self.stream(&ls_args)
     }
 
     /// Stage the .side-tracked file using git plumbing.
@@ -255,3 +1007,51 @@ impl SideRepo {
         Ok(())
     }
 }
+
+/// `git add` arguments: `flags`, then `paths`, then `exclude` pathspecs.
+fn add_args(flags: &[&str], paths: &[PathBuf], exclude: &[String]) -> Vec<String> {
+    let mut args = vec!["add".to_string()];
+    args.extend(flags.iter().map(|f| (*f).to_string()));
+    args.push("--".to_string());
+    args.extend(paths.iter().map(|p| p.to_string_lossy().into_owned()));
+    args.extend(exclude.iter().cloned());
+    args
+}
+
+/// Destination refs of the ` ! [rejected]  <src> -> <dst> (<reason>)` lines in `git push` output.
+fn rejected_refs(stderr: &str) -> Vec<String> {
+    stderr
+        .lines()
+        .filter(|line| line.contains("[rejected]"))
+        .filter_map(|line| {
+            let (_, dst) = line.split_once(" -> ")?;
+            Some(dst.split(" (").next().unwrap_or(dst).trim().to_string())
+        })
+        .collect()
+}
+
+/// Config key holding `key` of the recorded state for `remote`.
+fn state_key(remote: &str, key: &str) -> String {
+    format!("{STATE_SECTION}.{remote}.{key}")
+}
+
+/// One-line description of a failed push: git's `fatal:`/`error:` lines when there
+/// are any, otherwise everything but hints.
+fn push_error_summary(error: &Error) -> String {
+    let Error::GitCommandFailed(stderr) = error else {
+        return error.to_string();
+    };
+
+    let lines: Vec<&str> = stderr
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("hint:"))
+        .collect();
+    let errors: Vec<&str> = lines
+        .iter()
+        .copied()
+        .filter(|l| l.starts_with("fatal:") || l.starts_with("error:"))
+        .collect();
+
+    if errors.is_empty() { lines } else { errors }.join("; ")
+}
@@ -5,6 +5,52 @@ use std::path::{Path, PathBuf};
 use crate::error::{Error, Result};
 use crate::side_repo::SideRepo;
 
+/// Normalize a user-supplied path to be relative to the work tree.
+#[must_use]
+pub fn normalize(work_tree: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.strip_prefix(work_tree)
+            .map_or_else(|_| path.to_path_buf(), Path::to_path_buf)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+/// Render a relative path with `/` separators, as git prints it.
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Pending changes beneath one tracked path: what is staged in the side index, and
+/// how the work tree differs from the index (what the next `auto` would stage).
+pub struct PathStatus {
+    /// The tracked path as listed in `.side-tracked`.
+    pub path: PathBuf,
+    /// Whether the tracked path itself exists in the work tree.
+    pub exists: bool,
+    /// Changes staged in the side index, as `(status, path)` with status `A`, `M` or `D`.
+    pub staged: Vec<(char, String)>,
+    /// Files on disk not yet in the side index.
+    pub new: Vec<String>,
+    /// Files whose content differs from the side index.
+    pub modified: Vec<String>,
+    /// Files in the side index that are gone from disk.
+    pub deleted: Vec<String>,
+    /// New or modified files over `side.maxFileSize`, with their sizes; staging skips them.
+    pub too_large: Vec<(String, u64)>,
+}
+
+impl PathStatus {
+    /// Whether the next commit or `auto` would record something for this path.
+    #[must_use]
+    pub fn has_changes(&self) -> bool {
+        !(self.staged.is_empty()
+            && self.new.is_empty()
+            && self.modified.is_empty()
+            && self.deleted.is_empty())
+    }
+}
+
 /// Manages the .side-tracked file.
 pub struct TrackedPaths {
     file_path: PathBuf,
@@ -87,29 +133,137 @@ impl TrackedPaths {
         self.paths.is_empty()
     }
 
+    /// Check if a path is tracked or lies inside a tracked directory.
+    #[must_use]
+    pub fn covers(&self, path: &Path) -> bool {
+        self.paths.iter().any(|tracked| path.starts_with(tracked))
+    }
+
     /// Get all tracked paths.
     #[must_use]
     pub const fn paths(&self) -> &BTreeSet<PathBuf> {
         &self.paths
     }
 
+    /// Tracked paths that do not exist in the work tree.
+    #[must_use]
+    pub fn missing(&self, work_tree: &Path) -> Vec<PathBuf> {
+        self.paths
+            .iter()
+            .filter(|p| !work_tree.join(p).exists())
+            .cloned()
+            .collect()
+    }
+
     /// Expand all tracked paths to actual files on disk.
     /// Directories are walked recursively.
     #[must_use]
     pub fn expand(&self, work_tree: &Path) -> Vec<PathBuf> {
+        self.paths
+            .iter()
+            .flat_map(|path| Self::expand_path(work_tree, path))
+            .collect()
+    }
+
+    /// Expand a single tracked path to the files on disk beneath it.
+    /// Returns nothing if the path doesn't exist (it will be handled as a deletion).
+    #[must_use]
+    pub fn expand_path(work_tree: &Path, path: &Path) -> Vec<PathBuf> {
         let mut files = Vec::new();
+        let full_path = work_tree.join(path);
+        if full_path.is_file() {
+            files.push(path.to_path_buf());
+        } else if full_path.is_dir() {
+            Self::walk_dir(&full_path, path, &mut files);
+        }
+        files
+    }
 
-        for path in &self.paths {
-            let full_path = work_tree.join(path);
-            if full_path.is_file() {
-                files.push(path.clone());
-            } else if full_path.is_dir() {
-                Self::walk_dir(&full_path, path, &mut files);
+    /// Compare each tracked path on disk against the side index, and the side index
+    /// against the last side commit. New or modified files larger than `limit` bytes
+    /// are reported apart, since staging leaves them out.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the side index or diff cannot be read.
+    pub fn status(&self, repo: &SideRepo, limit: Option<u64>) -> Result<Vec<PathStatus>> {
+        if self.paths.is_empty() {
+            return Ok(Vec::new());
+        }
+        let roots: Vec<String> = self.paths.iter().map(|p| slash_path(p)).collect();
+        let scoped = |args: &[&str]| {
+            let mut args = args.to_vec();
+            args.push("--");
+            args.extend(roots.iter().map(String::as_str));
+            repo.git(&args)
+        };
+        let split = |out: String| -> BTreeSet<String> {
+            out.split('\0')
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+
+        let indexed = split(scoped(&["ls-files", "-z"])?);
+        let changed = split(scoped(&["diff-files", "-z", "--name-only"])?);
+
+        // "<status>\0<path>\0" pairs
+        let cached = scoped(&["diff", "--cached", "-z", "--name-status", "--no-renames"])?;
+        let mut fields = cached.split('\0').filter(|f| !f.is_empty());
+        let mut staged = Vec::new();
+        while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+            if let Some(status) = status.chars().next() {
+                staged.push((status, path.to_string()));
             }
-            // If path doesn't exist, skip it (will be handled as deletion)
         }
 
-        files
+        let statuses = self
+            .paths
+            .iter()
+            .map(|root| {
+                let on_disk: BTreeSet<String> = Self::expand_path(&repo.work_tree, root)
+                    .iter()
+                    .map(|p| slash_path(p))
+                    .collect();
+                let root_str = slash_path(root);
+                let under_root =
+                    |p: &str| p == root_str || p.starts_with(&format!("{root_str}/"));
+                let in_index: BTreeSet<String> =
+                    indexed.iter().filter(|p| under_root(p)).cloned().collect();
+
+                let mut new: Vec<String> = on_disk.difference(&in_index).cloned().collect();
+                let mut modified: Vec<String> = on_disk
+                    .intersection(&in_index)
+                    .filter(|p| changed.contains(*p))
+                    .cloned()
+                    .collect();
+
+                // Staging leaves out files over the limit, so they are listed apart
+                let too_large: Vec<(String, u64)> = new
+                    .iter()
+                    .chain(&modified)
+                    .filter_map(|p| {
+                        let size = fs::metadata(repo.work_tree.join(p)).ok()?.len();
+                        limit.is_some_and(|limit| size > limit).then(|| (p.clone(), size))
+                    })
+                    .collect();
+                let fits = |p: &String| !too_large.iter().any(|(large, _)| large == p);
+                new.retain(fits);
+                modified.retain(fits);
+
+                PathStatus {
+                    path: root.clone(),
+                    exists: repo.work_tree.join(root).exists(),
+                    staged: staged.iter().filter(|(_, p)| under_root(p)).cloned().collect(),
+                    new,
+                    modified,
+                    deleted: in_index.difference(&on_disk).cloned().collect(),
+                    too_large,
+                }
+            })
+            .collect();
+
+        Ok(statuses)
     }
 
     /// Recursively walk a directory and collect all files.